@@ -1,4 +1,118 @@
-from typing import Any
+from typing import Any, TypedDict
 
-def repair_json(json_str: str, /) -> Any: ...
+class JsonRepairError(ValueError):
+    msg: str
+    pos: int
+    lineno: int
+    colno: int
+    expected: list[str]
+    snippet: str
+    path: str | None
 
+class RepairEvent(TypedDict):
+    kind: str
+    offset: int
+    byte_offset: int
+
+class RepairedDocument(TypedDict):
+    value: Any
+    start: int
+    end: int
+    byte_start: int
+    byte_end: int
+    events: list[RepairEvent]
+
+class ExtractedMatch(TypedDict):
+    value: Any
+    start: int
+    end: int
+    fields: dict[str, tuple[int, int]] | None
+
+class JsonExtractor:
+    def __init__(self, schema: dict[str, Any], /, *, fuzzy_enums: bool = False) -> None: ...
+    def extract(self, text: bytes, /) -> Any: ...
+    def extract_span(self, text: bytes, /, *, fields: bool = False) -> ExtractedMatch: ...
+    def extract_all(self, text: bytes, /, *, fields: bool = False) -> list[ExtractedMatch]: ...
+
+def repair_json(
+    text: str,
+    *,
+    strict: bool = False,
+    allow_comments: bool = True,
+    allow_code_fences: bool = True,
+    allow_python_literals: bool = True,
+    allow_non_finite: bool = True,
+    allow_single_quotes: bool = True,
+    allow_trailing_commas: bool = True,
+    auto_close: bool = True,
+    scan_prefix: bool = True,
+    allow_trailing_content: bool = True,
+    allow_loose_tokens: bool = True,
+    allow_unquoted_keys: bool = False,
+    insert_missing_commas: bool = False,
+    max_depth: int = 128,
+    max_string_len: int = 1048576,
+    max_input_len: int = 67108864,
+) -> Any: ...
+def repair_json_str(
+    text: str,
+    *,
+    pretty: bool = False,
+    indent: int = 2,
+    strict: bool = False,
+    allow_comments: bool = True,
+    allow_code_fences: bool = True,
+    allow_python_literals: bool = True,
+    allow_non_finite: bool = True,
+    allow_single_quotes: bool = True,
+    allow_trailing_commas: bool = True,
+    auto_close: bool = True,
+    scan_prefix: bool = True,
+    allow_trailing_content: bool = True,
+    allow_loose_tokens: bool = True,
+    allow_unquoted_keys: bool = False,
+    insert_missing_commas: bool = False,
+    max_depth: int = 128,
+    max_string_len: int = 1048576,
+    max_input_len: int = 67108864,
+) -> str: ...
+def repair_json_report(
+    text: str,
+    *,
+    strict: bool = False,
+    allow_comments: bool = True,
+    allow_code_fences: bool = True,
+    allow_python_literals: bool = True,
+    allow_non_finite: bool = True,
+    allow_single_quotes: bool = True,
+    allow_trailing_commas: bool = True,
+    auto_close: bool = True,
+    scan_prefix: bool = True,
+    allow_trailing_content: bool = True,
+    allow_loose_tokens: bool = True,
+    allow_unquoted_keys: bool = False,
+    insert_missing_commas: bool = False,
+    max_depth: int = 128,
+    max_string_len: int = 1048576,
+    max_input_len: int = 67108864,
+) -> tuple[Any, list[RepairEvent]]: ...
+def repair_json_all(
+    text: str,
+    *,
+    strict: bool = False,
+    allow_comments: bool = True,
+    allow_code_fences: bool = True,
+    allow_python_literals: bool = True,
+    allow_non_finite: bool = True,
+    allow_single_quotes: bool = True,
+    allow_trailing_commas: bool = True,
+    auto_close: bool = True,
+    scan_prefix: bool = True,
+    allow_trailing_content: bool = True,
+    allow_loose_tokens: bool = True,
+    allow_unquoted_keys: bool = False,
+    insert_missing_commas: bool = False,
+    max_depth: int = 128,
+    max_string_len: int = 1048576,
+    max_input_len: int = 67108864,
+) -> list[RepairedDocument]: ...
@@ -11,8 +11,11 @@ echo "=== 2. Setting up Python Environment ==="
 
 echo "=== 3. Running Tests ==="
 echo "=== 3. Running Tests ==="
+# 0. Pure Rust path (no pyo3 at all)
+cargo test --no-default-features --test native_test
+
 # 1. Build the test binary without running it
-cargo test --no-default-features --test integration_test --no-run
+cargo test --test integration_test --no-run
 
 # 2. Find the executable (it's in target/debug/deps/integration_test-*)
 # We pick the most recent one
@@ -0,0 +1,404 @@
+use crate::repair::{self, RepairError, RepairErrorKind, RepairEvent, RepairOptions};
+use crate::structural::context::{FieldSpan, ParseContext};
+use crate::structural::{self, parser::ParseError, schema::SchemaNode};
+use crate::utils::cursor::Cursor;
+use crate::utils::location::Location;
+use pyo3::create_exception;
+use pyo3::exceptions::{PyRecursionError, PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use std::sync::Arc;
+
+create_exception!(
+    llm_json_utils,
+    JsonRepairError,
+    PyValueError,
+    "修复/提取失败，属性与 json.JSONDecodeError 对齐：msg、pos、lineno、colno，另有 expected 与 snippet"
+);
+
+/// pos 的单位：str 输入用字符下标，bytes 输入用字节偏移
+#[derive(Clone, Copy)]
+enum Pos {
+    Char(usize),
+    Byte(usize),
+}
+
+/// 构造带位置属性的 JsonRepairError；消息里的位置与 pos 属性单位一致
+fn located_error(
+    msg: &str,
+    pos: Pos,
+    location: &Location,
+    expected: Vec<String>,
+    path: Option<String>,
+) -> PyErr {
+    Python::with_gil(|py| {
+        let (unit, pos) = match pos {
+            Pos::Char(pos) => ("char", pos),
+            Pos::Byte(pos) => ("byte", pos),
+        };
+        let err = JsonRepairError::new_err(format!(
+            "{msg}: line {} column {} ({unit} {pos})",
+            location.line, location.column
+        ));
+        let value = err.value(py);
+        let attrs = [
+            ("msg", msg.into_py(py)),
+            ("pos", pos.into_py(py)),
+            ("lineno", location.line.into_py(py)),
+            ("colno", location.column.into_py(py)),
+            ("expected", expected.into_py(py)),
+            ("snippet", location.snippet.as_str().into_py(py)),
+            ("path", path.into_py(py)),
+        ];
+        for (name, attr) in attrs {
+            if let Err(e) = value.setattr(name, attr) {
+                return e;
+            }
+        }
+        err
+    })
+}
+
+impl From<RepairError> for PyErr {
+    fn from(err: RepairError) -> PyErr {
+        let location = err.location();
+        // 与结构化提取一致：嵌套超限抛 RecursionError，位置同样用字符下标
+        if let RepairErrorKind::DepthLimit(_) = err.kind() {
+            return PyRecursionError::new_err(format!(
+                "{}: line {} column {} (char {})",
+                err.message(),
+                location.line,
+                location.column,
+                location.char_offset
+            ));
+        }
+        let expected = err.expected().iter().map(|s| s.to_string()).collect();
+        // 输入是 str，pos 用字符下标，和 json.JSONDecodeError 一致
+        located_error(
+            &err.message(),
+            Pos::Char(location.char_offset),
+            location,
+            expected,
+            None,
+        )
+    }
+}
+
+/// 提取失败：输入是 bytes，pos 用字节偏移
+fn extraction_error(text: &[u8], err: ParseError) -> PyErr {
+    if let ParseError::RecursionLimit { .. } = err {
+        return PyRecursionError::new_err(err.to_string());
+    }
+    let location = Location::new(text, err.offset());
+    located_error(
+        &err.to_string(),
+        Pos::Byte(location.offset),
+        &location,
+        err.expected(),
+        err.path(),
+    )
+}
+
+/// 把 `**options` 关键字参数转成 RepairOptions：先看 strict 决定起点，再逐项覆盖；
+/// 未知参数名按 Python 惯例抛 TypeError
+fn repair_options(kwargs: Option<&PyDict>) -> PyResult<RepairOptions> {
+    let Some(kwargs) = kwargs else {
+        return Ok(RepairOptions::default());
+    };
+    let mut options = match kwargs.get_item("strict")? {
+        Some(strict) if strict.extract::<bool>()? => RepairOptions::strict(),
+        _ => RepairOptions::default(),
+    };
+    for (key, value) in kwargs.iter() {
+        let name: &str = key.extract()?;
+        let flag = match name {
+            "strict" => continue,
+            "allow_comments" => &mut options.allow_comments,
+            "allow_code_fences" => &mut options.allow_code_fences,
+            "allow_python_literals" => &mut options.allow_python_literals,
+            "allow_non_finite" => &mut options.allow_non_finite,
+            "allow_single_quotes" => &mut options.allow_single_quotes,
+            "allow_trailing_commas" => &mut options.allow_trailing_commas,
+            "auto_close" => &mut options.auto_close,
+            "scan_prefix" => &mut options.scan_prefix,
+            "allow_trailing_content" => &mut options.allow_trailing_content,
+            "allow_loose_tokens" => &mut options.allow_loose_tokens,
+            "allow_unquoted_keys" => &mut options.allow_unquoted_keys,
+            "insert_missing_commas" => &mut options.insert_missing_commas,
+            "max_depth" | "max_string_len" | "max_input_len" => {
+                let limit = match name {
+                    "max_depth" => &mut options.max_depth,
+                    "max_string_len" => &mut options.max_string_len,
+                    _ => &mut options.max_input_len,
+                };
+                *limit = value.extract()?;
+                continue;
+            }
+            _ => {
+                return Err(PyTypeError::new_err(format!(
+                    "unexpected keyword argument '{name}'"
+                )))
+            }
+        };
+        *flag = value.extract()?;
+    }
+    Ok(options)
+}
+
+/// 严格修复 JSON 字符串（Python 入口，纯 Rust 调用请用 `repair::repair_json`）
+#[pyfunction]
+#[pyo3(signature = (text, **options))]
+pub fn repair_json(py: Python, text: &str, options: Option<&PyDict>) -> PyResult<PyObject> {
+    let (value, _) = repair::repair_json_with_options(text, &repair_options(options)?)?;
+    value.to_py(py)
+}
+
+/// 修复并返回 (对象, 修复事件列表)，事件为 {"kind", "offset", "byte_offset"} 字典，
+/// offset 是 str 下标，byte_offset 是 UTF-8 字节偏移
+#[pyfunction]
+#[pyo3(signature = (text, **options))]
+pub fn repair_json_report(
+    py: Python,
+    text: &str,
+    options: Option<&PyDict>,
+) -> PyResult<(PyObject, PyObject)> {
+    let (value, events) = repair::repair_json_with_options(text, &repair_options(options)?)?;
+    Ok((value.to_py(py)?, events_to_py(py, text, (0, 0), &events)?))
+}
+
+/// `from` 是一对已知对应的 (字节偏移, 字符下标)，且不晚于第一个事件；
+/// 事件按偏移有序，字符下标从那里一次扫描增量算出
+fn events_to_py(
+    py: Python,
+    text: &str,
+    from: (usize, usize),
+    events: &[RepairEvent],
+) -> PyResult<PyObject> {
+    let list = PyList::empty(py);
+    let (mut last, mut chars) = from;
+    for event in events {
+        chars += text[last..event.offset].chars().count();
+        last = event.offset;
+
+        let item = PyDict::new(py);
+        item.set_item("kind", event.kind.as_str())?;
+        item.set_item("offset", chars)?;
+        item.set_item("byte_offset", event.offset)?;
+        list.append(item)?;
+    }
+    Ok(list.into())
+}
+
+/// 修复文本中所有互不重叠的 JSON 文档，返回 {"value", "start", "end", "byte_start", "byte_end",
+/// "events"} 字典列表；start/end 是 str 下标（可直接切片），byte_* 是 UTF-8 字节偏移，
+/// events 是该文档的修复事件，格式同 repair_json_report
+#[pyfunction]
+#[pyo3(signature = (text, **options))]
+pub fn repair_json_all(py: Python, text: &str, options: Option<&PyDict>) -> PyResult<PyObject> {
+    let documents = repair::repair_json_all_with_options(text, &repair_options(options)?)?;
+    let list = PyList::empty(py);
+    // 文档按偏移有序，与事件一样增量换算字符下标
+    let mut chars = 0;
+    let mut last = 0;
+    for doc in documents {
+        chars += text[last..doc.start].chars().count();
+        let start = chars;
+        chars += text[doc.start..doc.end].chars().count();
+        last = doc.end;
+
+        let item = PyDict::new(py);
+        item.set_item("value", doc.value.to_py(py)?)?;
+        item.set_item("start", start)?;
+        item.set_item("end", chars)?;
+        item.set_item("byte_start", doc.start)?;
+        item.set_item("byte_end", doc.end)?;
+        item.set_item(
+            "events",
+            events_to_py(py, text, (doc.start, start), &doc.events)?,
+        )?;
+        list.append(item)?;
+    }
+    Ok(list.into())
+}
+
+/// 修复后重新输出为合法的 JSON 文本（保留键顺序与数字写法）
+#[pyfunction]
+#[pyo3(signature = (text, *, pretty = false, indent = 2, **options))]
+pub fn repair_json_str(
+    text: &str,
+    pretty: bool,
+    indent: usize,
+    options: Option<&PyDict>,
+) -> PyResult<String> {
+    let (value, _) = repair::repair_json_with_options(text, &repair_options(options)?)?;
+    Ok(if pretty {
+        value.to_json_pretty(indent)
+    } else {
+        value.to_json()
+    })
+}
+
+/// 基于 Schema 的 JSON 提取器
+#[pyclass]
+struct JsonExtractor {
+    root: Arc<SchemaNode>,
+    fuzzy_enums: bool,
+}
+
+/// 一次成功的匹配：值、字节区间，以及按需收集的字段位置表
+struct Match {
+    value: PyObject,
+    start: usize,
+    end: usize,
+    fields: Option<Vec<FieldSpan>>,
+}
+
+impl Match {
+    /// {"value", "start", "end", "fields"}，fields 为 {JSON Pointer: (start, end)} 或 None
+    fn into_py_dict(self, py: Python) -> PyResult<PyObject> {
+        let item = PyDict::new(py);
+        item.set_item("value", self.value)?;
+        item.set_item("start", self.start)?;
+        item.set_item("end", self.end)?;
+        match self.fields {
+            Some(spans) => {
+                let fields = PyDict::new(py);
+                for span in spans {
+                    fields.set_item(span.pointer, (span.start, span.end))?;
+                }
+                item.set_item("fields", fields)?;
+            }
+            None => item.set_item("fields", py.None())?,
+        }
+        Ok(item.into())
+    }
+}
+
+impl JsonExtractor {
+    /// 从 `from` 起逐个候选起点尝试，返回第一个匹配；一个都没有时返回走得最远的失败
+    fn scan(
+        &self,
+        py: Python,
+        text: &[u8],
+        from: usize,
+        spans: bool,
+    ) -> Result<Match, Option<ParseError>> {
+        // 记录走得最远的失败，全部候选都失败时报告它
+        let mut furthest: Option<ParseError> = None;
+
+        // 大海捞针：按根节点类型寻找 JSON 起始
+        let mut start_pos = from;
+        while let Some(abs_idx) = structural::parser::find_root_start(&self.root, text, start_pos) {
+            let mut cursor = Cursor::at(text, abs_idx);
+            let ctx = if spans {
+                ParseContext::with_spans()
+            } else {
+                ParseContext::new()
+            };
+            let mut ctx = ctx.with_fuzzy_enums(self.fuzzy_enums);
+
+            match structural::parser::parse_node_with(&mut cursor, &self.root, py, &mut ctx) {
+                Ok(value) => {
+                    return Ok(Match {
+                        value,
+                        start: abs_idx,
+                        end: cursor.offset(),
+                        fields: ctx.into_spans(),
+                    })
+                }
+                Err(err) => {
+                    // 结构完整但取值被拒（约束、enum、整数、类型不符、`{}` 子树读不了）：
+                    // 它内部嵌套的值同样不是答案，跳过整个候选，而不是在它里面继续找
+                    let rejected = matches!(
+                        err,
+                        ParseError::ConstraintViolation { .. }
+                            | ParseError::NotInEnum { .. }
+                            | ParseError::InvalidInteger { .. }
+                            | ParseError::TypeMismatch { .. }
+                            | ParseError::InvalidValue { .. }
+                    );
+                    if furthest.as_ref().is_none_or(|f| err.offset() > f.offset()) {
+                        furthest = Some(err);
+                    }
+                    // 解析失败，继续找下一个
+                    start_pos = if rejected {
+                        structural::parser::candidate_end(text, abs_idx).unwrap_or(abs_idx + 1)
+                    } else {
+                        abs_idx + 1
+                    };
+                }
+            }
+        }
+        Err(furthest)
+    }
+}
+
+/// 全部候选都失败时的异常
+fn no_match_error(text: &[u8], root: &SchemaNode, furthest: Option<ParseError>) -> PyErr {
+    match furthest {
+        Some(err) => extraction_error(text, err),
+        None => located_error(
+            "No matching JSON found",
+            Pos::Byte(0),
+            &Location::new(text, 0),
+            structural::parser::root_expected(root),
+            None,
+        ),
+    }
+}
+
+#[pymethods]
+impl JsonExtractor {
+    /// fuzzy_enums=True 时，enum 的字符串成员按忽略大小写、首尾空白和全角差异匹配，
+    /// 命中后返回规范的成员值
+    #[new]
+    #[pyo3(signature = (schema_obj, /, *, fuzzy_enums = false))]
+    fn new(schema_obj: &PyAny, fuzzy_enums: bool) -> PyResult<Self> {
+        let root = structural::compiler::compile(schema_obj).map_err(|e| {
+            let message = e.value(schema_obj.py());
+            pyo3::exceptions::PyValueError::new_err(format!("Invalid schema: {message}"))
+        })?;
+        Ok(JsonExtractor { root, fuzzy_enums })
+    }
+
+    fn extract(&self, py: Python, text: &[u8]) -> PyResult<PyObject> {
+        match self.scan(py, text, 0, false) {
+            Ok(found) => Ok(found.value),
+            Err(furthest) => Err(no_match_error(text, &self.root, furthest)),
+        }
+    }
+
+    /// 与 extract 相同，但返回 {"value", "start", "end", "fields"}：start/end 是匹配的字节区间，
+    /// fields=True 时 "fields" 为 {JSON Pointer: (start, end)}，便于在原文中高亮或剥离 JSON
+    #[pyo3(signature = (text, /, *, fields = false))]
+    fn extract_span(&self, py: Python, text: &[u8], fields: bool) -> PyResult<PyObject> {
+        match self.scan(py, text, 0, fields) {
+            Ok(found) => found.into_py_dict(py),
+            Err(furthest) => Err(no_match_error(text, &self.root, furthest)),
+        }
+    }
+
+    /// 提取所有互不重叠、符合 Schema 的对象，每项形如 extract_span 的返回值（字节偏移）
+    #[pyo3(signature = (text, /, *, fields = false))]
+    fn extract_all(&self, py: Python, text: &[u8], fields: bool) -> PyResult<PyObject> {
+        let list = PyList::empty(py);
+        let mut from = 0;
+        while let Ok(found) = self.scan(py, text, from, fields) {
+            // 从对象之后继续，保证结果互不重叠
+            from = found.end.max(found.start + 1);
+            list.append(found.into_py_dict(py)?)?;
+        }
+        Ok(list.into())
+    }
+}
+
+#[pymodule]
+fn llm_json_utils(py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(repair_json, m)?)?;
+    m.add_function(wrap_pyfunction!(repair_json_str, m)?)?;
+    m.add_function(wrap_pyfunction!(repair_json_report, m)?)?;
+    m.add_function(wrap_pyfunction!(repair_json_all, m)?)?;
+    m.add_class::<JsonExtractor>()?;
+    m.add("JsonRepairError", py.get_type::<JsonRepairError>())?;
+    Ok(())
+}
@@ -0,0 +1,127 @@
+use crate::utils::location::Location;
+use std::fmt;
+
+/// What went wrong, independent of where.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RepairErrorKind {
+    UnexpectedEof,
+    UnexpectedChar(char),
+    /// A bare word that is not one of the accepted literals (`true`, `null`, `NaN`, ...).
+    InvalidLiteral,
+    InvalidNumber(String),
+    /// Neither the whole input nor any `{` / `[` in it starts a repairable document.
+    NoJsonFound,
+    /// More nested containers than `RepairOptions::max_depth` (the limit).
+    DepthLimit(usize),
+    /// A string longer than `RepairOptions::max_string_len` bytes (the limit).
+    StringTooLong(usize),
+    /// Input longer than `RepairOptions::max_input_len` bytes (the limit).
+    InputTooLarge(usize),
+}
+
+impl RepairErrorKind {
+    /// Size-guard violations: the input is refused as a whole rather than repaired.
+    pub fn is_limit(&self) -> bool {
+        matches!(
+            self,
+            RepairErrorKind::DepthLimit(_)
+                | RepairErrorKind::StringTooLong(_)
+                | RepairErrorKind::InputTooLarge(_)
+        )
+    }
+}
+
+/// Structural error raised when the input cannot be repaired deterministically.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepairError {
+    kind: RepairErrorKind,
+    expected: &'static [&'static str],
+    location: Location,
+}
+
+impl RepairError {
+    pub(crate) fn new(
+        kind: RepairErrorKind,
+        offset: usize,
+        expected: &'static [&'static str],
+    ) -> Self {
+        RepairError {
+            kind,
+            expected,
+            location: Location::unresolved(offset),
+        }
+    }
+
+    /// Fills in line/column/snippet once the error is about to leave the parser.
+    pub(crate) fn resolve(mut self, input: &str) -> Self {
+        self.location.resolve(input.as_bytes());
+        self
+    }
+
+    pub fn kind(&self) -> &RepairErrorKind {
+        &self.kind
+    }
+
+    /// Tokens that would have been accepted at the failing position.
+    pub fn expected(&self) -> &'static [&'static str] {
+        self.expected
+    }
+
+    pub fn location(&self) -> &Location {
+        &self.location
+    }
+
+    pub fn offset(&self) -> usize {
+        self.location.offset
+    }
+
+    pub fn line(&self) -> usize {
+        self.location.line
+    }
+
+    pub fn column(&self) -> usize {
+        self.location.column
+    }
+
+    pub fn snippet(&self) -> &str {
+        &self.location.snippet
+    }
+
+    /// Human-readable message without the position suffix (like `JSONDecodeError.msg`).
+    pub fn message(&self) -> String {
+        let what = match &self.kind {
+            RepairErrorKind::UnexpectedEof => "Unexpected end of input".to_string(),
+            RepairErrorKind::UnexpectedChar(ch) => format!("Unexpected character {ch:?}"),
+            RepairErrorKind::InvalidLiteral => "Invalid literal".to_string(),
+            RepairErrorKind::InvalidNumber(literal) => {
+                format!("Invalid number literal {literal:?}")
+            }
+            RepairErrorKind::NoJsonFound => "No valid JSON found".to_string(),
+            RepairErrorKind::DepthLimit(limit) => {
+                format!("Nesting deeper than {limit} levels")
+            }
+            RepairErrorKind::StringTooLong(limit) => format!("String longer than {limit} bytes"),
+            RepairErrorKind::InputTooLarge(limit) => format!("Input larger than {limit} bytes"),
+        };
+        if self.expected.is_empty() {
+            what
+        } else {
+            format!("{what}, expected {}", self.expected.join(" or "))
+        }
+    }
+}
+
+impl fmt::Display for RepairError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: line {} column {} (byte {})",
+            self.message(),
+            self.location.line,
+            self.location.column,
+            self.location.offset
+        )
+    }
+}
+
+impl std::error::Error for RepairError {}
@@ -1,394 +1,824 @@
-use pyo3::exceptions::PyValueError;
-use pyo3::prelude::*;
-use pyo3::types::{PyDict, PyList, PyString};
-use std::iter::Peekable;
-use std::str::Chars;
+use crate::utils::cursor::Cursor;
+use crate::utils::escape;
+use std::borrow::Cow;
+
+mod error;
+mod options;
+mod report;
+pub mod value;
+mod writer;
+
+pub use error::{RepairError, RepairErrorKind};
+pub use options::{
+    RepairOptions, DEFAULT_MAX_DEPTH, DEFAULT_MAX_INPUT_LEN, DEFAULT_MAX_STRING_LEN,
+};
+pub use report::{RepairEvent, RepairKind};
+pub use value::{JsonValue, Number};
+
+pub type RepairResult<T> = Result<T, RepairError>;
 
 struct Parser<'a> {
-    chars: Peekable<Chars<'a>>,
+    source: &'a str,
+    cursor: Cursor<'a>,
+    options: RepairOptions,
+    /// Containers currently open, bounded by `options.max_depth`
+    depth: usize,
+    events: Vec<RepairEvent>,
 }
 
 impl<'a> Parser<'a> {
-    fn new(source: &'a str) -> Self {
+    fn new(source: &'a str, start: usize, options: RepairOptions) -> Self {
         Parser {
-            chars: source.chars().peekable(),
+            source,
+            cursor: Cursor::at(source.as_bytes(), start),
+            options,
+            depth: 0,
+            events: Vec::new(),
         }
     }
 
-    fn skip_whitespace_and_comments(&mut self) {
-        loop {
-            let Some(&ch) = self.chars.peek() else {
-                return;
-            };
-
-            if ch.is_whitespace() {
-                self.chars.next();
-                continue;
-            }
+    #[inline(always)]
+    fn pos(&self) -> usize {
+        self.cursor.pos
+    }
 
-            if ch == '#' {
-                self.consume_until_newline();
-                continue;
-            }
+    #[inline(always)]
+    fn peek(&self) -> Option<u8> {
+        self.cursor.remaining().first().copied()
+    }
 
-            if ch == '/' {
-                self.chars.next(); // consume '/'
-                match self.chars.peek() {
-                    Some('/') => {
-                        self.consume_until_newline();
-                        continue;
-                    }
-                    Some('*') => {
-                        self.chars.next(); // consume '*'
-                        self.consume_block_comment();
-                        continue;
-                    }
-                    _ => {
-                        return;
-                    }
-                }
-            }
+    /// Full char at the cursor; only needed off the hot path (errors, non-ASCII whitespace).
+    fn peek_char(&self) -> Option<char> {
+        self.source[self.pos()..].chars().next()
+    }
 
-            // Markdown-style fenced code blocks: ```json ... ```
-            if ch == '`' {
-                self.chars.next(); // 1st
-                if let Some('`') = self.chars.peek() {
-                    self.chars.next(); // 2nd
-                    if let Some('`') = self.chars.peek() {
-                        self.chars.next(); // 3rd
-                        self.consume_fence_block();
-                        continue;
-                    }
-                }
-                return;
-            }
+    /// Error at the current position: the char under the cursor, or EOF.
+    fn unexpected(&self, expected: &'static [&'static str]) -> RepairError {
+        let kind = match self.peek_char() {
+            Some(ch) => RepairErrorKind::UnexpectedChar(ch),
+            None => RepairErrorKind::UnexpectedEof,
+        };
+        RepairError::new(kind, self.pos(), expected)
+    }
 
-            return;
-        }
+    fn record(&mut self, kind: RepairKind, offset: usize) {
+        self.events.push(RepairEvent { kind, offset });
     }
 
-    fn consume_until_newline(&mut self) {
-        while let Some(ch) = self.chars.next() {
-            if ch == '\n' {
-                break;
-            }
-        }
+    /// Events in input order (closing-time events such as trailing commas are recorded late).
+    fn into_events(mut self) -> Vec<RepairEvent> {
+        self.events.sort_by_key(|e| e.offset);
+        self.events
     }
 
-    fn consume_block_comment(&mut self) {
-        let mut last_was_star = false;
-        while let Some(ch) = self.chars.next() {
-            if last_was_star && ch == '/' {
-                return;
-            }
-            last_was_star = ch == '*';
-        }
+    /// Moves the cursor to `offset` bytes past `needle`'s next occurrence, or to EOF.
+    fn skip_past(&mut self, needle: &[u8]) {
+        let rest = self.cursor.remaining();
+        let skip = memchr::memmem::find(rest, needle).map_or(rest.len(), |i| i + needle.len());
+        self.cursor.advance(skip);
     }
 
-    fn consume_fence_block(&mut self) {
-        // Skip until the next ``` or EOF. We don't try to interpret the language tag.
-        let mut backtick_count = 0usize;
-        while let Some(ch) = self.chars.next() {
-            if ch == '`' {
-                backtick_count += 1;
-                if backtick_count == 3 {
+    fn skip_whitespace_and_comments(&mut self) {
+        loop {
+            self.cursor.skip_whitespace();
+            let Some(b) = self.peek() else {
+                return;
+            };
+            let start = self.pos();
+            let rest = self.cursor.remaining();
+
+            match b {
+                b'#' | b'/' if !self.options.allow_comments => return,
+                b'#' => {
+                    self.skip_past(b"\n");
+                    self.record(RepairKind::StrippedComment, start);
+                }
+                // A lone '/' is left in place so the error points at it
+                b'/' => {
+                    match rest.get(1) {
+                        Some(b'/') => self.skip_past(b"\n"),
+                        Some(b'*') => {
+                            self.cursor.advance(2);
+                            self.skip_past(b"*/");
+                        }
+                        _ => return,
+                    }
+                    self.record(RepairKind::StrippedComment, start);
+                }
+                // Markdown-style fenced code blocks: ```json ... ```
+                // Only the fence and its language tag are skipped, the block's content is parsed.
+                b'`' if !self.options.allow_code_fences => return,
+                b'`' if rest.starts_with(b"```") => {
+                    self.cursor.advance(3 + fence_tag_len(&rest[3..]));
+                    self.record(RepairKind::StrippedFence, start);
+                }
+                // A lone backtick (inline code such as `{"a": `1`}`) is dropped as well
+                b'`' => {
+                    self.cursor.advance(1);
+                    self.record(RepairKind::StrippedFence, start);
                     return;
                 }
-            } else {
-                backtick_count = 0;
+                0x0b | 0x0c | 0x80.. if !self.options.allow_loose_tokens => return,
+                // Vertical tab / form feed are whitespace too, the cursor only skips the JSON four
+                0x0b | 0x0c => {
+                    self.cursor.advance(1);
+                    self.record(RepairKind::LooseToken, start);
+                }
+                // Non-ASCII whitespace (NBSP, ideographic space, ...)
+                0x80.. => match self.peek_char() {
+                    Some(ch) if ch.is_whitespace() => {
+                        self.cursor.advance(ch.len_utf8());
+                        self.record(RepairKind::LooseToken, start);
+                    }
+                    _ => return,
+                },
+                _ => return,
             }
         }
     }
 
-    fn parse_value(&mut self, py: Python<'a>) -> PyResult<PyObject> {
+    fn parse_value(&mut self) -> RepairResult<JsonValue<'a>> {
         self.skip_whitespace_and_comments();
 
-        let Some(&ch) = self.chars.peek() else {
-            return Err(PyValueError::new_err(
-                "Unexpected end of input while expecting a value",
-            ));
+        let Some(b) = self.peek() else {
+            return Err(self.unexpected(&["a value"]));
         };
+        let start = self.pos();
 
-        match ch {
-            '{' => self.parse_object(py),
-            '[' => self.parse_array(py),
-            '"' | '\'' => self.parse_string(py),
-            't' | 'T' => {
+        match b {
+            b'{' | b'[' => {
+                if self.depth >= self.options.max_depth {
+                    let limit = RepairErrorKind::DepthLimit(self.options.max_depth);
+                    return Err(RepairError::new(limit, start, &[]));
+                }
+                self.depth += 1;
+                let container = if b == b'{' {
+                    self.parse_object()
+                } else {
+                    self.parse_array()
+                };
+                self.depth -= 1;
+                container
+            }
+            b'"' => self.parse_string().map(JsonValue::String),
+            b'\'' if self.options.allow_single_quotes => self.parse_string().map(JsonValue::String),
+            b't' | b'T' => {
                 if self.match_literal("true") {
-                    Ok(true.into_py(py))
+                    Ok(JsonValue::Bool(true))
                 } else {
-                    Err(PyValueError::new_err("Invalid boolean literal"))
+                    Err(invalid_literal(start, &["true"]))
                 }
             }
-            'f' | 'F' => {
+            b'f' | b'F' => {
                 if self.match_literal("false") {
-                    Ok(false.into_py(py))
+                    Ok(JsonValue::Bool(false))
                 } else {
-                    Err(PyValueError::new_err("Invalid boolean literal"))
+                    Err(invalid_literal(start, &["false"]))
                 }
             }
-            'n' | 'N' => {
+            b'n' | b'N' => {
                 // Support both "null" and "None"
-                if self.match_literal("null") {
-                    Ok(py.None())
-                } else if self.match_literal("none") {
-                    Ok(py.None())
+                if self.match_literal("null") || self.match_literal("none") {
+                    Ok(JsonValue::Null)
                 } else if self.match_literal("nan") {
-                    Ok(f64::NAN.into_py(py))
+                    Ok(JsonValue::Number(Number::Float(f64::NAN)))
                 } else {
-                    Err(PyValueError::new_err("Invalid null/None/NaN literal"))
+                    let expected: &'static [&'static str] = match (
+                        self.options.allow_python_literals,
+                        self.options.allow_non_finite,
+                    ) {
+                        (true, true) => &["null", "None", "NaN"],
+                        (true, false) => &["null", "None"],
+                        (false, true) => &["null", "NaN"],
+                        (false, false) => &["null"],
+                    };
+                    Err(invalid_literal(start, expected))
                 }
             }
-            'i' | 'I' => {
-                if self.match_literal("infinity") {
-                    Ok(f64::INFINITY.into_py(py))
-                } else if self.match_literal("inf") {
-                    Ok(f64::INFINITY.into_py(py))
+            b'i' | b'I' if self.options.allow_non_finite => {
+                if self.match_literal("infinity") || self.match_literal("inf") {
+                    Ok(JsonValue::Number(Number::Float(f64::INFINITY)))
                 } else {
-                    Err(PyValueError::new_err("Invalid infinity literal"))
+                    Err(invalid_literal(start, &["Infinity"]))
                 }
             }
-            '-' | '+' | '0'..='9' | '.' => self.parse_number(py),
-            _ => Err(PyValueError::new_err(format!(
-                "Unexpected character {ch:?} while parsing value"
-            ))),
+            b'-' | b'+' | b'0'..=b'9' | b'.' => self.parse_number(),
+            _ => Err(self.unexpected(&["a value"])),
         }
     }
 
-    fn parse_object(&mut self, py: Python<'a>) -> PyResult<PyObject> {
-        let dict = PyDict::new(py);
-        self.chars.next(); // skip '{'
+    fn parse_object(&mut self) -> RepairResult<JsonValue<'a>> {
+        let mut members = Vec::new();
+        self.cursor.advance(1); // skip '{'
+
+        // Offset of the separator comma consumed after the last member
+        let mut pending_comma = None;
 
         loop {
             self.skip_whitespace_and_comments();
-            let ch = self.chars.peek().copied();
+            let b = self.peek();
 
-            if ch.is_none() || ch == Some('}') {
-                if ch == Some('}') {
-                    self.chars.next();
+            if b.is_none() || b == Some(b'}') {
+                if let Some(offset) = pending_comma {
+                    if !self.options.allow_trailing_commas {
+                        return Err(self.unexpected(&["a string key"]));
+                    }
+                    self.record(RepairKind::TrailingComma, offset);
+                }
+                if b == Some(b'}') {
+                    self.cursor.advance(1);
+                } else {
+                    self.auto_close(RepairKind::AutoClosedObject, &["a string key", "'}'"])?;
                 }
-                return Ok(dict.into());
+                return Ok(JsonValue::Object(members));
             }
 
-            if ch == Some(',') {
-                self.chars.next();
+            if b == Some(b',') && self.options.allow_trailing_commas {
+                self.record(RepairKind::ExtraComma, self.pos());
+                self.cursor.advance(1);
                 continue;
             }
 
             // Parse Key
-            // STRICT: Keys MUST be strings (quoted)
-            let ch = self.chars.peek().copied();
-            if ch != Some('"') && ch != Some('\'') {
-                return Err(PyValueError::new_err("Object keys must be strings"));
-            }
-
-            let key_obj = self.parse_string(py)?;
-            if key_obj.downcast::<PyString>(py).is_err() {
-                return Err(PyValueError::new_err("Object keys must be strings"));
-            }
+            // Keys must be strings unless bare identifiers were opted into
+            let quoted = b == Some(b'"') || (b == Some(b'\'') && self.options.allow_single_quotes);
+            let key = if quoted {
+                self.parse_string()?
+            } else if let Some(key) = self.parse_identifier_key() {
+                key
+            } else {
+                return Err(self.unexpected(&["a string key", "'}'"]));
+            };
 
             self.skip_whitespace_and_comments();
-            match self.chars.peek().copied() {
-                Some(':') => {
-                    self.chars.next();
-                }
-                _ => {
-                    return Err(PyValueError::new_err("Expected ':' after object key"));
-                }
+            if self.peek() != Some(b':') {
+                return Err(self.unexpected(&["':'"]));
             }
+            self.cursor.advance(1);
 
-            let value = self.parse_value(py)?;
-            dict.set_item(&key_obj, value)?;
+            let value = self.parse_value()?;
+            members.push((key, value));
 
+            let value_end = self.pos();
             self.skip_whitespace_and_comments();
-            let ch = self.chars.peek().copied();
-            if ch == Some(',') {
-                self.chars.next();
-                continue;
-            }
-            if ch == Some('}') {
-                self.chars.next();
-                return Ok(dict.into_py(py));
-            }
-            if ch.is_none() {
-                return Ok(dict.into_py(py));
+            match self.peek() {
+                Some(b',') => {
+                    pending_comma = Some(self.pos());
+                    self.cursor.advance(1);
+                }
+                Some(b'}') => {
+                    self.cursor.advance(1);
+                    return Ok(JsonValue::Object(members));
+                }
+                None => {
+                    self.auto_close(RepairKind::AutoClosedObject, &["','", "'}'"])?;
+                    return Ok(JsonValue::Object(members));
+                }
+                Some(_) if self.options.insert_missing_commas && self.at_key() => {
+                    self.record(RepairKind::MissingComma, value_end);
+                }
+                // If we are here, we expected ',' or '}' but got something else.
+                Some(_) => return Err(self.unexpected(&["','", "'}'"])),
             }
-            // If we are here, we expected ',' or '}' but got something else.
-            return Err(PyValueError::new_err("Expected ',' or '}' in object"));
         }
     }
 
-    fn parse_array(&mut self, py: Python<'a>) -> PyResult<PyObject> {
-        let list = PyList::empty(py);
-        self.chars.next(); // skip '['
+    fn parse_array(&mut self) -> RepairResult<JsonValue<'a>> {
+        let mut items = Vec::new();
+        self.cursor.advance(1); // skip '['
+        let mut pending_comma = None;
 
         loop {
             self.skip_whitespace_and_comments();
-            let ch = self.chars.peek().copied();
+            let b = self.peek();
 
-            if ch.is_none() || ch == Some(']') {
-                if ch == Some(']') {
-                    self.chars.next();
+            if b.is_none() || b == Some(b']') {
+                if let Some(offset) = pending_comma {
+                    if !self.options.allow_trailing_commas {
+                        return Err(self.unexpected(&["a value"]));
+                    }
+                    self.record(RepairKind::TrailingComma, offset);
+                }
+                if b == Some(b']') {
+                    self.cursor.advance(1);
+                } else {
+                    self.auto_close(RepairKind::AutoClosedArray, &["a value", "']'"])?;
                 }
-                return Ok(list.into_py(py));
+                return Ok(JsonValue::Array(items));
             }
-            if ch == Some(',') {
-                self.chars.next();
+            if b == Some(b',') && self.options.allow_trailing_commas {
+                self.record(RepairKind::ExtraComma, self.pos());
+                self.cursor.advance(1);
                 continue;
             }
 
-            let value = self.parse_value(py)?;
-            list.append(value)?;
+            items.push(self.parse_value()?);
 
+            let value_end = self.pos();
             self.skip_whitespace_and_comments();
-            let ch = self.chars.peek().copied();
-            if ch == Some(',') {
-                self.chars.next();
-                continue;
+            match self.peek() {
+                Some(b',') => {
+                    pending_comma = Some(self.pos());
+                    self.cursor.advance(1);
+                }
+                Some(b']') => {
+                    self.cursor.advance(1);
+                    return Ok(JsonValue::Array(items));
+                }
+                None => {
+                    self.auto_close(RepairKind::AutoClosedArray, &["','", "']'"])?;
+                    return Ok(JsonValue::Array(items));
+                }
+                Some(b) if self.options.insert_missing_commas && self.starts_value(b) => {
+                    self.record(RepairKind::MissingComma, value_end);
+                }
+                Some(_) => return Err(self.unexpected(&["','", "']'"])),
             }
-            if ch == Some(']') {
-                self.chars.next();
-                return Ok(list.into_py(py));
+        }
+    }
+
+    /// Bare `{name: ...}` key: an identifier (`_`, `$`, letters, then digits too), Unicode
+    /// letters included. Only taken when a `:` follows, so prose never turns into a key.
+    fn parse_identifier_key(&mut self) -> Option<Cow<'a, str>> {
+        let start = self.pos();
+        let len = self.identifier_key_len()?;
+        self.cursor.advance(len);
+        self.record(RepairKind::UnquotedKey, start);
+        Some(Cow::Borrowed(&self.source[start..start + len]))
+    }
+
+    /// Length of the bare identifier key at the cursor, if unquoted keys are enabled.
+    fn identifier_key_len(&self) -> Option<usize> {
+        if !self.options.allow_unquoted_keys {
+            return None;
+        }
+        let rest = &self.source[self.pos()..];
+        let mut chars = rest.char_indices();
+        let first = chars.next().map(|(_, ch)| ch)?;
+        if !(first.is_alphabetic() || first == '_' || first == '$') {
+            return None;
+        }
+        let len = chars
+            .find(|&(_, ch)| !(ch.is_alphanumeric() || ch == '_' || ch == '$'))
+            .map_or(rest.len(), |(i, _)| i);
+
+        rest[len..].trim_start().starts_with(':').then_some(len)
+    }
+
+    /// Whether an object key starts at the cursor (used to insert a missing comma).
+    fn at_key(&self) -> bool {
+        match self.peek() {
+            Some(b'"') => true,
+            Some(b'\'') => self.options.allow_single_quotes,
+            _ => self.identifier_key_len().is_some(),
+        }
+    }
+
+    /// Whether `b` can start an array element (used to insert a missing comma).
+    fn starts_value(&self, b: u8) -> bool {
+        match b {
+            b'{' | b'[' | b'"' | b'-' | b'+' | b'.' | b'0'..=b'9' => true,
+            b'\'' => self.options.allow_single_quotes,
+            b't' | b'T' | b'f' | b'F' | b'n' | b'N' | b'i' | b'I' => true,
+            _ => false,
+        }
+    }
+
+    /// Borrows escape-free strings straight from the input; only escapes force a copy.
+    fn parse_string(&mut self) -> RepairResult<Cow<'a, str>> {
+        let start = self.pos();
+        let Some(quote) = self.peek() else {
+            return Err(self.unexpected(&["'\"'"]));
+        };
+        if quote == b'\'' {
+            self.record(RepairKind::SingleQuotedString, start);
+        }
+        self.cursor.advance(1);
+
+        let closing: &'static [&'static str] = if quote == b'"' { &["'\"'"] } else { &["\"'\""] };
+        let mut out: Option<String> = None;
+        let mut chunk_start = self.pos();
+
+        loop {
+            let rest = self.cursor.remaining();
+            let Some(idx) = memchr::memchr2(quote, b'\\', rest) else {
+                // EOF inside the string: keep what we have
+                self.check_control_chars(rest)?;
+                self.cursor.advance(rest.len());
+                self.check_string_len(start)?;
+                let chunk = &self.source[chunk_start..self.pos()];
+                self.auto_close(RepairKind::AutoClosedString, closing)?;
+                return Ok(finish_string(out, chunk));
+            };
+
+            self.check_control_chars(&rest[..idx])?;
+            self.cursor.advance(idx);
+            self.check_string_len(start)?;
+            let chunk = &self.source[chunk_start..self.pos()];
+            if rest[idx] == quote {
+                self.cursor.advance(1);
+                return Ok(finish_string(out, chunk));
             }
-            if ch.is_none() {
-                return Ok(list.into_py(py));
+
+            let buf = out.get_or_insert_with(String::new);
+            buf.push_str(chunk);
+            self.cursor.advance(1); // skip '\\'
+            if !self.decode_escape(buf)? {
+                self.auto_close(RepairKind::AutoClosedString, closing)?;
+                return Ok(Cow::Owned(out.unwrap_or_default()));
             }
-            return Err(PyValueError::new_err("Expected ',' or ']' in array"));
+            chunk_start = self.pos();
         }
     }
 
-    fn parse_string(&mut self, py: Python<'a>) -> PyResult<PyObject> {
-        let quote = self.chars.next().ok_or_else(|| {
-            PyValueError::new_err("Unexpected end of input while starting string")
-        })?;
-        let mut out = String::new();
-
-        while let Some(ch) = self.chars.next() {
-            if ch == '\\' {
-                let Some(esc) = self.chars.next() else {
-                    break;
-                };
-                match esc {
-                    'n' => out.push('\n'),
-                    'r' => out.push('\r'),
-                    't' => out.push('\t'),
-                    'b' => out.push('\x08'),
-                    'f' => out.push('\x0c'),
-                    '"' | '\'' | '\\' | '/' => out.push(esc),
-                    'u' => {
-                        let mut buffer = ['\0'; 4];
-                        let mut count = 0usize;
-                        let mut valid_hex = true;
-                        for i in 0..4 {
-                            if let Some(h) = self.chars.next() {
-                                if !h.is_ascii_hexdigit() {
-                                    valid_hex = false;
-                                }
-                                buffer[i] = h;
-                                count += 1;
-                            } else {
-                                valid_hex = false;
-                                break;
-                            }
-                        }
-                        if valid_hex && count == 4 {
-                            let s: String = buffer.iter().collect();
-                            if let Ok(code) = u32::from_str_radix(&s, 16) {
-                                if let Some(c) = char::from_u32(code) {
-                                    out.push(c);
-                                    continue;
-                                }
-                            }
-                        }
-                        out.push_str("\\u");
-                        for i in 0..count {
-                            out.push(buffer[i]);
-                        }
-                    }
-                    other => {
-                        out.push('\\');
-                        out.push(other);
-                    }
-                }
+    /// Raw control characters (U+0000–U+001F, e.g. a literal newline) in the string bytes
+    /// starting at the cursor; JSON requires them escaped.
+    fn check_control_chars(&mut self, chunk: &[u8]) -> RepairResult<()> {
+        let base = self.pos();
+        for (i, &b) in chunk.iter().enumerate() {
+            if b >= 0x20 {
                 continue;
             }
-
-            if ch == quote {
-                return Ok(PyString::new(py, &out).into_py(py));
+            if !self.options.allow_loose_tokens {
+                let kind = RepairErrorKind::UnexpectedChar(b as char);
+                return Err(RepairError::new(kind, base + i, &["an escape sequence"]));
             }
+            self.record(RepairKind::LooseToken, base + i);
+        }
+        Ok(())
+    }
 
-            out.push(ch);
+    /// Fails once the string opened at `start` has more than `max_string_len` raw bytes.
+    fn check_string_len(&self, start: usize) -> RepairResult<()> {
+        let limit = self.options.max_string_len;
+        if self.pos() - start - 1 > limit {
+            return Err(RepairError::new(
+                RepairErrorKind::StringTooLong(limit),
+                start,
+                &[],
+            ));
         }
+        Ok(())
+    }
 
-        Ok(PyString::new(py, &out).into_py(py))
+    /// Decodes the escape after a backslash into `out`. Unknown escapes and broken `\u`
+    /// sequences are preserved verbatim (or rejected without `allow_loose_tokens`), surrogate
+    /// pairs are combined. Returns `false` on EOF.
+    fn decode_escape(&mut self, out: &mut String) -> RepairResult<bool> {
+        let rest = self.cursor.remaining();
+        let Some(len) = escape::decode_escape(rest, out) else {
+            return Ok(false);
+        };
+        // `\'` is not JSON either; a broken `\u` consumes fewer than four hex digits
+        let loose = len == 0 || rest[0] == b'\'' || (rest[0] == b'u' && len < 5);
+        if loose {
+            if !self.options.allow_loose_tokens {
+                return Err(self.unexpected(&["an escape sequence"]));
+            }
+            self.record(RepairKind::LooseToken, self.pos() - 1);
+        }
+        self.cursor.advance(len);
+        Ok(true)
     }
 
-    fn parse_number(&mut self, py: Python<'a>) -> PyResult<PyObject> {
-        let mut s = String::new();
-        while let Some(&ch) = self.chars.peek() {
-            if ch.is_ascii_digit() || matches!(ch, '-' | '+' | '.' | 'e' | 'E') {
-                s.push(ch);
-                self.chars.next();
-            } else {
-                break;
+    fn parse_number(&mut self) -> RepairResult<JsonValue<'a>> {
+        let start = self.pos();
+        let len = self
+            .cursor
+            .remaining()
+            .iter()
+            .take_while(|b| b.is_ascii_digit() || matches!(b, b'-' | b'+' | b'.' | b'e' | b'E'))
+            .count();
+        self.cursor.advance(len);
+        let s = &self.source[start..start + len];
+
+        if !is_json_number(s) && !s.is_empty() {
+            if !self.options.allow_loose_tokens {
+                return Err(RepairError::new(
+                    RepairErrorKind::InvalidNumber(s.to_string()),
+                    start,
+                    &[],
+                ));
             }
+            self.record(RepairKind::LooseToken, start);
         }
 
-        if s.contains('.') || s.contains('e') || s.contains('E') {
+        if s.contains(['.', 'e', 'E']) {
             if let Ok(f) = s.parse::<f64>() {
-                return Ok(f.into_py(py));
+                // `1e400` overflows to infinity, which only the non-finite leniency allows
+                if f.is_infinite() {
+                    if !self.options.allow_non_finite {
+                        return Err(RepairError::new(
+                            RepairErrorKind::InvalidNumber(s.to_string()),
+                            start,
+                            &[],
+                        ));
+                    }
+                    self.record(RepairKind::NonFiniteNumber, start);
+                }
+                // Keep valid spellings such as `1e3` or `1.50` for the writer; loose ones
+                // (`.5`, `1.`) and the shortest form itself need no literal
+                if f.is_finite() && is_json_number(s) && format!("{f:?}") != s {
+                    let literal = s.to_string();
+                    return Ok(JsonValue::Number(Number::FloatLiteral {
+                        value: f,
+                        literal,
+                    }));
+                }
+                return Ok(JsonValue::Number(Number::Float(f)));
             }
         } else if let Ok(i) = s.parse::<i64>() {
-            return Ok(i.into_py(py));
-        } else {
-            // Fallback: delegate big integers to Python's arbitrary-precision int
-            let builtins = py.import("builtins")?;
-            let py_int = builtins.getattr("int")?.call1((s.clone(),))?;
-            return Ok(py_int.into());
+            return Ok(JsonValue::Number(Number::Int(i)));
+        } else if is_integer_literal(s) {
+            // Too big for i64: keep the digits, consumers decide on the precision
+            return Ok(JsonValue::Number(Number::BigInt(s.to_string())));
         }
 
-        Err(PyValueError::new_err(format!(
-            "Invalid number literal {s:?}"
-        )))
+        Err(RepairError::new(
+            RepairErrorKind::InvalidNumber(s.to_string()),
+            start,
+            &[],
+        ))
     }
 
+    /// Case-insensitive literal match; advances only on success.
     fn match_literal(&mut self, expected: &str) -> bool {
-        let mut cursor = self.chars.clone();
-        for c in expected.chars() {
-            match cursor.next() {
-                Some(got) if got.to_ascii_lowercase() == c => {}
-                _ => return false,
-            }
+        let start = self.pos();
+        let rest = self.cursor.remaining();
+        if rest.len() < expected.len()
+            || !rest[..expected.len()].eq_ignore_ascii_case(expected.as_bytes())
+        {
+            return false;
+        }
+
+        // Anything but the exact JSON spelling is a leniency worth reporting
+        let written = &rest[..expected.len()];
+        let kind = match expected {
+            "nan" | "infinity" | "inf" => Some(RepairKind::NonFiniteNumber),
+            "none" => Some(RepairKind::PythonLiteral),
+            _ if written != expected.as_bytes() => Some(RepairKind::PythonLiteral),
+            _ => None,
+        };
+        let allowed = match kind {
+            Some(RepairKind::NonFiniteNumber) => self.options.allow_non_finite,
+            Some(_) => self.options.allow_python_literals,
+            None => true,
+        };
+        if !allowed {
+            return false;
         }
-        // Only now advance the real iterator
-        for _ in 0..expected.len() {
-            self.chars.next();
+
+        self.cursor.advance(expected.len());
+        if let Some(kind) = kind {
+            self.record(kind, start);
         }
         true
     }
+
+    /// Reports text left after the document (the closing fence and comments do not count),
+    /// or fails on it when trailing content is not allowed.
+    fn finish(&mut self) -> RepairResult<()> {
+        self.skip_whitespace_and_comments();
+        if self.peek().is_some() {
+            if !self.options.allow_trailing_content {
+                return Err(self.unexpected(&["end of input"]));
+            }
+            self.record(RepairKind::TrailingContent, self.pos());
+        }
+        Ok(())
+    }
+
+    /// Closes whatever is still open at EOF, or fails when auto-closing is off.
+    fn auto_close(
+        &mut self,
+        kind: RepairKind,
+        expected: &'static [&'static str],
+    ) -> RepairResult<()> {
+        if !self.options.auto_close {
+            return Err(self.unexpected(expected));
+        }
+        self.record(kind, self.pos());
+        Ok(())
+    }
+}
+
+fn finish_string<'a>(out: Option<String>, chunk: &'a str) -> Cow<'a, str> {
+    match out {
+        Some(mut buf) => {
+            buf.push_str(chunk);
+            Cow::Owned(buf)
+        }
+        None => Cow::Borrowed(chunk),
+    }
+}
+
+/// Language tag right after an opening fence (```` ```json ````).
+fn fence_tag_len(rest: &[u8]) -> usize {
+    rest.iter()
+        .take_while(|b| b.is_ascii_alphanumeric() || matches!(b, b'_' | b'-' | b'+'))
+        .count()
+}
+
+/// Offset of the opening fence that ends `prefix`, if only its language tag and
+/// whitespace separate it from the document.
+fn fence_before(prefix: &str) -> Option<usize> {
+    let fence = memchr::memmem::rfind(prefix.as_bytes(), b"```")?;
+    let after = &prefix.as_bytes()[fence + 3..];
+    let tag = fence_tag_len(after);
+    after[tag..]
+        .iter()
+        .all(u8::is_ascii_whitespace)
+        .then_some(fence)
+}
+
+fn invalid_literal(offset: usize, expected: &'static [&'static str]) -> RepairError {
+    RepairError::new(RepairErrorKind::InvalidLiteral, offset, expected)
+}
+
+/// RFC 8259 number grammar: `-?(0|[1-9][0-9]*)(\.[0-9]+)?([eE][+-]?[0-9]+)?`.
+fn is_json_number(s: &str) -> bool {
+    fn digits(s: &[u8]) -> usize {
+        s.iter().take_while(|b| b.is_ascii_digit()).count()
+    }
+    let s = s.as_bytes();
+    let mut i = usize::from(s.first() == Some(&b'-'));
+    match digits(&s[i..]) {
+        0 => return false,
+        n if n > 1 && s[i] == b'0' => return false,
+        n => i += n,
+    }
+    if s.get(i) == Some(&b'.') {
+        match digits(&s[i + 1..]) {
+            0 => return false,
+            n => i += 1 + n,
+        }
+    }
+    if matches!(s.get(i), Some(b'e' | b'E')) {
+        i += 1;
+        if matches!(s.get(i), Some(b'+' | b'-')) {
+            i += 1;
+        }
+        match digits(&s[i..]) {
+            0 => return false,
+            n => i += n,
+        }
+    }
+    i == s.len()
+}
+
+fn is_integer_literal(s: &str) -> bool {
+    let digits = s.strip_prefix(['-', '+']).unwrap_or(s);
+    !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit())
 }
 
-#[pyfunction]
-pub fn repair_json(py: Python<'_>, json_str: &str) -> PyResult<PyObject> {
+/// Repairs `json_str` into an interpreter-free [`JsonValue`] with every leniency enabled.
+///
+/// Tries the whole input first, then every `{` / `[` as a candidate start.
+pub fn repair_json(json_str: &str) -> RepairResult<JsonValue<'_>> {
+    repair_json_with_report(json_str).map(|(value, _)| value)
+}
+
+/// Like [`repair_json`], but also returns every fix that was applied, in input order.
+///
+/// When nothing parses, the error from the attempt that got furthest is returned.
+pub fn repair_json_with_report(json_str: &str) -> RepairResult<(JsonValue<'_>, Vec<RepairEvent>)> {
+    repair_json_with_options(json_str, &RepairOptions::default())
+}
+
+/// Like [`repair_json_with_report`], with the leniencies chosen by `options`.
+///
+/// Exceeding one of the size limits fails right away, without trying further start positions.
+pub fn repair_json_with_options<'a>(
+    json_str: &'a str,
+    options: &RepairOptions,
+) -> RepairResult<(JsonValue<'a>, Vec<RepairEvent>)> {
+    if json_str.len() > options.max_input_len {
+        let limit = RepairErrorKind::InputTooLarge(options.max_input_len);
+        return Err(RepairError::new(limit, 0, &[]).resolve(json_str));
+    }
+
     // 1. Try direct parse first (fast path)
-    let mut parser = Parser::new(json_str);
-    if let Ok(res) = parser.parse_value(py) {
-        return Ok(res);
+    let mut parser = Parser::new(json_str, 0, *options);
+    let mut furthest = match parser
+        .parse_value()
+        .and_then(|res| parser.finish().map(|_| res))
+    {
+        Ok(res) => return Ok((res, parser.into_events())),
+        Err(err) => err,
+    };
+    if !options.scan_prefix || furthest.kind().is_limit() {
+        return Err(furthest.resolve(json_str));
+    }
+    let mut candidates = 0usize;
+
+    // 2. If direct parse fails, try every '{' or '[' as a potential start position
+    for idx in memchr::memchr2_iter(b'{', b'[', json_str.as_bytes()) {
+        candidates += 1;
+        let mut sub_parser = Parser::new(json_str, idx, *options);
+        sub_parser.record(RepairKind::SkippedPrefix, idx);
+        if options.allow_code_fences {
+            if let Some(fence) = fence_before(&json_str[..idx]) {
+                sub_parser.record(RepairKind::StrippedFence, fence);
+            }
+        }
+        match sub_parser
+            .parse_value()
+            .and_then(|res| sub_parser.finish().map(|_| res))
+        {
+            Ok(res) => return Ok((res, sub_parser.into_events())),
+            Err(err) if err.kind().is_limit() => return Err(err.resolve(json_str)),
+            Err(err) if err.offset() > furthest.offset() => furthest = err,
+            Err(_) => {}
+        }
     }
 
-    // 2. If direct parse fails, try to find the first '{' or '['
-    // We iterate through the string to find potential start positions
-    let mut chars = json_str.char_indices().peekable();
+    // Plain prose with nothing to anchor on: "unexpected 'H'" would only be noise
+    let prose = matches!(
+        furthest.kind(),
+        RepairErrorKind::UnexpectedChar(_) | RepairErrorKind::InvalidLiteral
+    );
+    if candidates == 0 && prose {
+        furthest = RepairError::new(RepairErrorKind::NoJsonFound, furthest.offset(), &[]);
+    }
+    Err(furthest.resolve(json_str))
+}
+
+/// Repairs the single value starting at byte `start` of `json_str`, without skipping any
+/// prefix or looking past the value.
+///
+/// Returns the value and the byte offset right after it; error offsets are relative to
+/// `json_str`. Used by the schema extractor for `{}` sub-schemas.
+pub fn repair_value_at<'a>(
+    json_str: &'a str,
+    start: usize,
+    options: &RepairOptions,
+) -> RepairResult<(JsonValue<'a>, usize)> {
+    let mut parser = Parser::new(json_str, start, *options);
+    let value = parser.parse_value()?;
+    Ok((value, parser.cursor.offset()))
+}
+
+/// One document found by [`repair_json_all`], with its byte span in the input.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RepairedDocument<'a> {
+    pub value: JsonValue<'a>,
+    /// Byte offset of the opening `{` / `[`.
+    pub start: usize,
+    /// Byte offset right after the document (its closing bracket, or EOF when auto-closed).
+    pub end: usize,
+    /// Fixes applied to this document, offsets relative to the whole input.
+    pub events: Vec<RepairEvent>,
+}
 
-    while let Some((idx, ch)) = chars.next() {
-        if ch == '{' || ch == '[' {
-            // Try parsing from here
-            let mut sub_parser = Parser::new(&json_str[idx..]);
-            if let Ok(res) = sub_parser.parse_value(py) {
-                return Ok(res);
+/// Repairs every non-overlapping object or array in `json_str`, in input order.
+///
+/// Scanning resumes right after each document found; a `{` / `[` that does not start a
+/// repairable document is skipped. Text without any document gives an empty list.
+pub fn repair_json_all(json_str: &str) -> RepairResult<Vec<RepairedDocument<'_>>> {
+    repair_json_all_with_options(json_str, &RepairOptions::default())
+}
+
+/// Like [`repair_json_all`], with the leniencies chosen by `options` (`scan_prefix` is
+/// implied). Only the size limits produce an error.
+pub fn repair_json_all_with_options<'a>(
+    json_str: &'a str,
+    options: &RepairOptions,
+) -> RepairResult<Vec<RepairedDocument<'a>>> {
+    if json_str.len() > options.max_input_len {
+        let limit = RepairErrorKind::InputTooLarge(options.max_input_len);
+        return Err(RepairError::new(limit, 0, &[]).resolve(json_str));
+    }
+
+    let bytes = json_str.as_bytes();
+    let mut documents = Vec::new();
+    let mut pos = 0;
+    while let Some(idx) = memchr::memchr2(b'{', b'[', &bytes[pos..]) {
+        let start = pos + idx;
+        let mut parser = Parser::new(json_str, start, *options);
+        match parser.parse_value() {
+            Ok(value) => {
+                pos = parser.pos();
+                documents.push(RepairedDocument {
+                    value,
+                    start,
+                    end: pos,
+                    events: parser.into_events(),
+                });
             }
+            Err(err) if err.kind().is_limit() => return Err(err.resolve(json_str)),
+            Err(_) => pos = start + 1,
         }
     }
+    Ok(documents)
+}
 
-    Err(PyValueError::new_err("No valid JSON found"))
+/// Repairs `json_str` and re-emits it as RFC 8259 text.
+///
+/// `indent = None` gives compact output, `Some(n)` pretty-prints with `n` spaces.
+pub fn repair_json_str(json_str: &str, indent: Option<usize>) -> RepairResult<String> {
+    let value = repair_json(json_str)?;
+    Ok(match indent {
+        Some(width) => value.to_json_pretty(width),
+        None => value.to_json(),
+    })
 }
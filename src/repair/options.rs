@@ -0,0 +1,105 @@
+/// Switches for every leniency of the repair parser.
+///
+/// `Default` accepts everything the parser knows how to fix safely, which is what
+/// chat-facing callers want (riskier repairs such as unquoted keys or missing commas stay
+/// opt-in); [`RepairOptions::strict`] turns every leniency off, leaving plain RFC 8259
+/// parsing. Flip individual fields from either starting point:
+///
+/// ```
+/// use llm_json_utils::repair::{self, RepairOptions};
+///
+/// let options = RepairOptions {
+///     allow_non_finite: false,
+///     ..RepairOptions::default()
+/// };
+/// assert!(repair::repair_json_with_options(r#"{"x": NaN}"#, &options).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RepairOptions {
+    /// Skip `//`, `/* */` and `#` comments.
+    pub allow_comments: bool,
+    /// Skip Markdown code fences (```` ``` ````).
+    pub allow_code_fences: bool,
+    /// Accept `True` / `False` / `None` and other non-lowercase spellings of the literals.
+    pub allow_python_literals: bool,
+    /// Accept `NaN` / `Infinity` / `Inf`.
+    pub allow_non_finite: bool,
+    /// Accept `'...'` strings and keys.
+    pub allow_single_quotes: bool,
+    /// Drop trailing (`[1,]`), leading and repeated commas.
+    pub allow_trailing_commas: bool,
+    /// Close strings, arrays and objects still open at EOF.
+    pub auto_close: bool,
+    /// When the input does not start with a document, retry from every `{` / `[`.
+    pub scan_prefix: bool,
+    /// Ignore text after the document (`{"a": 1} hope this helps`).
+    pub allow_trailing_content: bool,
+    /// Accept number spellings outside JSON (`+1`, `01`, `.5`, `1.`), unknown escapes such
+    /// as `\q`, raw control characters inside strings (a literal newline or tab), and
+    /// whitespace JSON does not define (NBSP, vertical tab).
+    pub allow_loose_tokens: bool,
+    /// Accept JavaScript-style bare identifier keys (`{name: "x"}`). Off by default:
+    /// a bare word where a key belongs is as likely to be prose as a key.
+    pub allow_unquoted_keys: bool,
+    /// Insert the comma between two members written back to back (`[1 2]`,
+    /// `{"a": 1 "b": 2}`). Off by default; a missing `:` is still an error.
+    pub insert_missing_commas: bool,
+    /// Deepest container nesting accepted; deeper input fails with `DepthLimit` instead
+    /// of exhausting the native stack.
+    pub max_depth: usize,
+    /// Longest string accepted, in raw input bytes.
+    pub max_string_len: usize,
+    /// Largest input accepted, in bytes.
+    pub max_input_len: usize,
+}
+
+/// Same nesting cap as the structural extractor.
+pub const DEFAULT_MAX_DEPTH: usize = 128;
+/// Same string cap as the structural extractor (1 MB).
+pub const DEFAULT_MAX_STRING_LEN: usize = 1024 * 1024;
+pub const DEFAULT_MAX_INPUT_LEN: usize = 64 * 1024 * 1024;
+
+impl RepairOptions {
+    /// Every leniency off; the size limits keep their defaults.
+    pub const fn strict() -> Self {
+        RepairOptions {
+            allow_comments: false,
+            allow_code_fences: false,
+            allow_python_literals: false,
+            allow_non_finite: false,
+            allow_single_quotes: false,
+            allow_trailing_commas: false,
+            auto_close: false,
+            scan_prefix: false,
+            allow_trailing_content: false,
+            allow_loose_tokens: false,
+            allow_unquoted_keys: false,
+            insert_missing_commas: false,
+            max_depth: DEFAULT_MAX_DEPTH,
+            max_string_len: DEFAULT_MAX_STRING_LEN,
+            max_input_len: DEFAULT_MAX_INPUT_LEN,
+        }
+    }
+}
+
+impl Default for RepairOptions {
+    fn default() -> Self {
+        RepairOptions {
+            allow_comments: true,
+            allow_code_fences: true,
+            allow_python_literals: true,
+            allow_non_finite: true,
+            allow_single_quotes: true,
+            allow_trailing_commas: true,
+            auto_close: true,
+            scan_prefix: true,
+            allow_trailing_content: true,
+            allow_loose_tokens: true,
+            allow_unquoted_keys: false,
+            insert_missing_commas: false,
+            max_depth: DEFAULT_MAX_DEPTH,
+            max_string_len: DEFAULT_MAX_STRING_LEN,
+            max_input_len: DEFAULT_MAX_INPUT_LEN,
+        }
+    }
+}
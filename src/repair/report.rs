@@ -0,0 +1,72 @@
+use std::fmt;
+
+/// Category of a fix applied by the repair parser.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RepairKind {
+    /// Prose before the document was skipped; the offset is where the document starts.
+    SkippedPrefix,
+    /// `//`, `#` or `/* */` comment removed.
+    StrippedComment,
+    /// Markdown code fence (```) or stray inline-code backtick removed.
+    StrippedFence,
+    /// Comma right before `}` / `]` or EOF dropped.
+    TrailingComma,
+    /// Leading or repeated comma dropped.
+    ExtraComma,
+    /// Object still open at EOF.
+    AutoClosedObject,
+    /// Array still open at EOF.
+    AutoClosedArray,
+    /// String still open at EOF.
+    AutoClosedString,
+    /// `'...'` string accepted.
+    SingleQuotedString,
+    /// `True` / `False` / `None` (or another non-JSON spelling) accepted.
+    PythonLiteral,
+    /// `NaN` / `Infinity` accepted.
+    NonFiniteNumber,
+    /// Bare identifier accepted as an object key.
+    UnquotedKey,
+    /// Comma inserted between two members; the offset is right after the first one.
+    MissingComma,
+    /// Text after the document was ignored; the offset is where it starts.
+    TrailingContent,
+    /// Number spelling, escape, unescaped control character or whitespace outside the
+    /// JSON grammar accepted (`+1`, `.5`, `\\q`, a raw newline in a string, NBSP, ...).
+    LooseToken,
+}
+
+impl RepairKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RepairKind::SkippedPrefix => "SkippedPrefix",
+            RepairKind::StrippedComment => "StrippedComment",
+            RepairKind::StrippedFence => "StrippedFence",
+            RepairKind::TrailingComma => "TrailingComma",
+            RepairKind::ExtraComma => "ExtraComma",
+            RepairKind::AutoClosedObject => "AutoClosedObject",
+            RepairKind::AutoClosedArray => "AutoClosedArray",
+            RepairKind::AutoClosedString => "AutoClosedString",
+            RepairKind::SingleQuotedString => "SingleQuotedString",
+            RepairKind::PythonLiteral => "PythonLiteral",
+            RepairKind::NonFiniteNumber => "NonFiniteNumber",
+            RepairKind::UnquotedKey => "UnquotedKey",
+            RepairKind::MissingComma => "MissingComma",
+            RepairKind::TrailingContent => "TrailingContent",
+            RepairKind::LooseToken => "LooseToken",
+        }
+    }
+}
+
+impl fmt::Display for RepairKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// One fix applied while repairing, located by byte offset into the original input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RepairEvent {
+    pub kind: RepairKind,
+    pub offset: usize,
+}
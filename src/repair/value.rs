@@ -0,0 +1,136 @@
+use super::writer;
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+#[cfg(feature = "python")]
+use pyo3::types::{PyDict, PyList};
+use std::borrow::Cow;
+use std::fmt;
+
+/// Number as written in the source, already classified.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Number {
+    Int(i64),
+    /// Integers that do not fit into i64; digits are kept verbatim (optional sign included).
+    BigInt(String),
+    Float(f64),
+    /// Float whose JSON spelling differs from the shortest round-trip form (`1e3`, `1.50`);
+    /// the writer re-emits `literal` verbatim.
+    FloatLiteral {
+        value: f64,
+        literal: String,
+    },
+}
+
+/// Interpreter-free JSON tree produced by the repair parser.
+///
+/// Strings and keys borrow from the input whenever they contain no escapes; use
+/// [`JsonValue::into_owned`] to detach the tree from the source text.
+///
+/// Object members keep source order. Duplicate keys are kept as well; lookups and
+/// the Python conversion let the last occurrence win, like `json.loads` does.
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonValue<'a> {
+    Null,
+    Bool(bool),
+    Number(Number),
+    String(Cow<'a, str>),
+    Array(Vec<JsonValue<'a>>),
+    Object(Vec<(Cow<'a, str>, JsonValue<'a>)>),
+}
+
+impl<'a> JsonValue<'a> {
+    /// Looks up an object member (last occurrence wins). Returns `None` for non-objects.
+    pub fn get(&self, key: &str) -> Option<&JsonValue<'a>> {
+        match self {
+            JsonValue::Object(members) => {
+                members.iter().rev().find(|(k, _)| k == key).map(|(_, v)| v)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            JsonValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[JsonValue<'a>]> {
+        match self {
+            JsonValue::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, JsonValue::Null)
+    }
+
+    /// Copies every borrowed string so the tree no longer depends on the input.
+    pub fn into_owned(self) -> JsonValue<'static> {
+        match self {
+            JsonValue::Null => JsonValue::Null,
+            JsonValue::Bool(b) => JsonValue::Bool(b),
+            JsonValue::Number(n) => JsonValue::Number(n),
+            JsonValue::String(s) => JsonValue::String(Cow::Owned(s.into_owned())),
+            JsonValue::Array(items) => {
+                JsonValue::Array(items.into_iter().map(JsonValue::into_owned).collect())
+            }
+            JsonValue::Object(members) => JsonValue::Object(
+                members
+                    .into_iter()
+                    .map(|(k, v)| (Cow::Owned(k.into_owned()), v.into_owned()))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Compact RFC 8259 text; key order and number spellings are kept as parsed.
+    pub fn to_json(&self) -> String {
+        writer::to_json_string(self, None)
+    }
+
+    /// Pretty-printed RFC 8259 text with `indent` spaces per nesting level.
+    pub fn to_json_pretty(&self, indent: usize) -> String {
+        writer::to_json_string(self, Some(indent))
+    }
+
+    /// Converts the tree into native Python objects (`dict`, `list`, `str`, `int`, `float`, `bool`, `None`).
+    #[cfg(feature = "python")]
+    pub fn to_py(&self, py: Python<'_>) -> PyResult<PyObject> {
+        match self {
+            JsonValue::Null => Ok(py.None()),
+            JsonValue::Bool(b) => Ok(b.into_py(py)),
+            JsonValue::Number(Number::Int(i)) => Ok(i.into_py(py)),
+            JsonValue::Number(Number::Float(f))
+            | JsonValue::Number(Number::FloatLiteral { value: f, .. }) => Ok(f.into_py(py)),
+            JsonValue::Number(Number::BigInt(digits)) => {
+                // Python's int() gives us arbitrary precision for free
+                let builtins = py.import("builtins")?;
+                Ok(builtins.getattr("int")?.call1((digits.as_str(),))?.into())
+            }
+            JsonValue::String(s) => Ok(s.as_ref().into_py(py)),
+            JsonValue::Array(items) => {
+                let list = PyList::empty(py);
+                for item in items {
+                    list.append(item.to_py(py)?)?;
+                }
+                Ok(list.into())
+            }
+            JsonValue::Object(members) => {
+                let dict = PyDict::new(py);
+                for (key, value) in members {
+                    dict.set_item(key.as_ref(), value.to_py(py)?)?;
+                }
+                Ok(dict.into())
+            }
+        }
+    }
+}
+
+impl fmt::Display for JsonValue<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_json())
+    }
+}
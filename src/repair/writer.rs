@@ -0,0 +1,114 @@
+use super::value::{JsonValue, Number};
+use std::fmt::Write;
+
+/// Serializes `value` as RFC 8259 text.
+///
+/// `indent = None` gives the compact form; `Some(n)` pretty-prints with `n` spaces per level.
+/// Non-finite floats (`NaN`, `Infinity`) have no JSON spelling and are written as `null`.
+pub fn to_json_string(value: &JsonValue<'_>, indent: Option<usize>) -> String {
+    let mut out = String::new();
+    write_value(&mut out, value, indent, 0);
+    out
+}
+
+fn write_value(out: &mut String, value: &JsonValue<'_>, indent: Option<usize>, level: usize) {
+    match value {
+        JsonValue::Null => out.push_str("null"),
+        JsonValue::Bool(true) => out.push_str("true"),
+        JsonValue::Bool(false) => out.push_str("false"),
+        JsonValue::Number(n) => write_number(out, n),
+        JsonValue::String(s) => write_string(out, s),
+        JsonValue::Array(items) => {
+            if items.is_empty() {
+                out.push_str("[]");
+                return;
+            }
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_newline(out, indent, level + 1);
+                write_value(out, item, indent, level + 1);
+            }
+            write_newline(out, indent, level);
+            out.push(']');
+        }
+        JsonValue::Object(members) => {
+            if members.is_empty() {
+                out.push_str("{}");
+                return;
+            }
+            out.push('{');
+            for (i, (key, item)) in members.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_newline(out, indent, level + 1);
+                write_string(out, key);
+                out.push_str(if indent.is_some() { ": " } else { ":" });
+                write_value(out, item, indent, level + 1);
+            }
+            write_newline(out, indent, level);
+            out.push('}');
+        }
+    }
+}
+
+fn write_newline(out: &mut String, indent: Option<usize>, level: usize) {
+    if let Some(width) = indent {
+        out.push('\n');
+        out.extend(std::iter::repeat_n(' ', width * level));
+    }
+}
+
+fn write_number(out: &mut String, number: &Number) {
+    match number {
+        Number::Int(i) => {
+            let _ = write!(out, "{i}");
+        }
+        // JSON allows neither a leading '+' nor leading zeros (both accepted as loose tokens)
+        Number::BigInt(digits) => {
+            let (sign, magnitude) = match digits.strip_prefix('-') {
+                Some(magnitude) => ("-", magnitude),
+                None => ("", digits.strip_prefix('+').unwrap_or(digits)),
+            };
+            let magnitude = magnitude.trim_start_matches('0');
+            out.push_str(sign);
+            out.push_str(if magnitude.is_empty() { "0" } else { magnitude });
+        }
+        // Debug keeps the shortest round-trip form and always includes '.' or an exponent
+        Number::Float(f) if f.is_finite() => {
+            let _ = write!(out, "{f:?}");
+        }
+        Number::Float(_) => out.push_str("null"),
+        Number::FloatLiteral { literal, .. } => out.push_str(literal),
+    }
+}
+
+fn write_string(out: &mut String, s: &str) {
+    out.push('"');
+    let mut start = 0;
+    for (i, b) in s.bytes().enumerate() {
+        let escaped = match b {
+            b'"' => "\\\"",
+            b'\\' => "\\\\",
+            b'\n' => "\\n",
+            b'\r' => "\\r",
+            b'\t' => "\\t",
+            0x08 => "\\b",
+            0x0c => "\\f",
+            0x00..=0x1f => "",
+            _ => continue,
+        };
+        out.push_str(&s[start..i]);
+        if escaped.is_empty() {
+            let _ = write!(out, "\\u{b:04x}");
+        } else {
+            out.push_str(escaped);
+        }
+        start = i + 1;
+    }
+    out.push_str(&s[start..]);
+    out.push('"');
+}
@@ -1,107 +1,578 @@
-use super::schema::{FieldLookup, SchemaNode, SMALL_MAP_THRESHOLD};
+use super::constraints::{Check, Format};
+use super::schema::{Discriminator, FieldLookup, RefSlot, SchemaNode, SMALL_MAP_THRESHOLD};
+use crate::repair::{JsonValue, Number};
 use ahash::{AHashMap, AHashSet};
 use pyo3::prelude::*;
-use pyo3::types::{PyDict, PyList};
+use pyo3::types::{PyBool, PyDict, PyFloat, PyList, PyLong, PyString};
+use regex::Regex;
 use smallvec::SmallVec;
-use std::sync::Arc;
-
-pub fn compile(schema_obj: &PyAny) -> PyResult<SchemaNode> {
-    if let Ok(schema_dict) = schema_obj.downcast::<PyDict>() {
-        let type_val = schema_dict.get_item("type")?;
-
-        if let Some(t) = type_val {
-            let type_str = t.extract::<String>()?;
-            match type_str.as_str() {
-                "string" => Ok(SchemaNode::PrimitiveString),
-                "integer" | "number" => Ok(SchemaNode::PrimitiveNumber),
-                "boolean" => Ok(SchemaNode::PrimitiveBool),
-                "array" => {
-                    let items = schema_dict.get_item("items")?.ok_or_else(|| {
-                        PyErr::new::<pyo3::exceptions::PyValueError, _>(
-                            "Array schema missing 'items'",
-                        )
-                    })?;
-                    let inner_node = compile(items)?;
-                    Ok(SchemaNode::Array(Arc::new(inner_node)))
-                }
-                "object" => {
-                    let properties = schema_dict.get_item("properties")?;
-                    let required_list = schema_dict.get_item("required")?;
-
-                    let mut fields_vec = SmallVec::new();
-                    let mut fields_map = AHashMap::new();
-                    let mut patterns = Vec::new();
-                    let mut required_set = AHashSet::new();
-
-                    if let Some(props) = properties {
-                        if let Ok(props_dict) = props.downcast::<PyDict>() {
-                            for (k, v) in props_dict {
-                                let key_str = k.extract::<String>()?;
-                                let key_bytes = key_str.as_bytes().to_vec();
-                                let node = Arc::new(compile(v)?);
-
-                                // 构建 Aho-Corasick 模式
-                                // 1. 双引号: "key"
-                                let mut dq = Vec::with_capacity(key_bytes.len() + 2);
-                                dq.push(b'"');
-                                dq.extend_from_slice(&key_bytes);
-                                dq.push(b'"');
-                                patterns.push(dq);
-
-                                // 2. 单引号: 'key'
-                                let mut sq = Vec::with_capacity(key_bytes.len() + 2);
-                                sq.push(b'\'');
-                                sq.extend_from_slice(&key_bytes);
-                                sq.push(b'\'');
-                                patterns.push(sq);
-
-                                if props_dict.len() < SMALL_MAP_THRESHOLD {
-                                    fields_vec.push((key_bytes.clone(), node.clone()));
-                                } else {
-                                    fields_map.insert(key_bytes.clone(), node.clone());
+use std::borrow::Cow;
+use std::sync::{Arc, OnceLock};
+
+/// 编译整份 Schema；`$ref` 按 JSON Pointer 在根文档内解析，
+/// 同一目标只编译一次，所有引用方共享同一个节点
+pub fn compile(schema_obj: &PyAny) -> PyResult<Arc<SchemaNode>> {
+    let mut compiler = Compiler {
+        root: schema_obj,
+        resolved: AHashMap::new(),
+        pending: AHashMap::new(),
+        containers: 0,
+    };
+    // 根文档本身登记为 "#"，`{"$ref": "#"}` 因此也能递归
+    compiler.compile_ref("#")
+}
+
+struct Compiler<'py> {
+    root: &'py PyAny,
+    /// 已编译完成的 `$ref` 目标
+    resolved: AHashMap<String, Arc<SchemaNode>>,
+    /// 正在编译的 `$ref` 目标：回填槽，以及开始编译时所在的容器层数
+    pending: AHashMap<String, (RefSlot, usize)>,
+    /// 当前位于多少层 properties / items 之内
+    containers: usize,
+}
+
+impl<'py> Compiler<'py> {
+    fn compile(&mut self, schema_obj: &'py PyAny) -> PyResult<Arc<SchemaNode>> {
+        if let Ok(schema_dict) = schema_obj.downcast::<PyDict>() {
+            if let Some(reference) = schema_dict.get_item("$ref")? {
+                return self.compile_ref(reference.extract::<&str>()?);
+            }
+        }
+        self.compile_inline(schema_obj).map(Arc::new)
+    }
+
+    fn compile_ref(&mut self, reference: &str) -> PyResult<Arc<SchemaNode>> {
+        if let Some(node) = self.resolved.get(reference) {
+            return Ok(node.clone());
+        }
+        if let Some((slot, containers)) = self.pending.get(reference) {
+            // 回指自身：中间必须隔着对象或数组，否则解析时会原地打转
+            if *containers == self.containers {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "Circular $ref without an object or array in between: {reference}"
+                )));
+            }
+            return Ok(Arc::new(SchemaNode::Ref(slot.clone())));
+        }
+
+        let target = self.lookup(reference)?;
+        let slot = Arc::new(OnceLock::new());
+        self.pending
+            .insert(reference.to_string(), (slot.clone(), self.containers));
+        let node = self.compile(target);
+        self.pending.remove(reference);
+        let node = node?;
+        let _ = slot.set(Arc::downgrade(&node));
+        self.resolved.insert(reference.to_string(), node.clone());
+        Ok(node)
+    }
+
+    /// 只支持本文档内的引用：`#`、`#/$defs/Name`、`#/definitions/Name` 等 JSON Pointer
+    fn lookup(&self, reference: &str) -> PyResult<&'py PyAny> {
+        let unsupported = || {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Unsupported $ref: {reference}"
+            ))
+        };
+        let pointer = reference.strip_prefix('#').ok_or_else(unsupported)?;
+        if pointer.is_empty() {
+            return Ok(self.root);
+        }
+        let pointer = pointer.strip_prefix('/').ok_or_else(unsupported)?;
+
+        let mut node = self.root;
+        for token in pointer.split('/') {
+            let token = token.replace("~1", "/").replace("~0", "~");
+            node = if let Ok(dict) = node.downcast::<PyDict>() {
+                dict.get_item(token.as_str())?
+            } else if let Ok(list) = node.downcast::<PyList>() {
+                token
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|idx| list.get_item(idx).ok())
+            } else {
+                None
+            }
+            .ok_or_else(unsupported)?;
+        }
+        Ok(node)
+    }
+
+    fn compile_inline(&mut self, schema_obj: &'py PyAny) -> PyResult<SchemaNode> {
+        let node = self.compile_type(schema_obj)?;
+        let Ok(schema_dict) = schema_obj.downcast::<PyDict>() else {
+            return Ok(node);
+        };
+
+        let checks = compile_checks(schema_dict)?;
+        let node = if checks.is_empty() {
+            node
+        } else {
+            SchemaNode::Checked {
+                inner: Arc::new(node),
+                checks,
+            }
+        };
+
+        // enum / const：包一层 Enum，解析后校验取值
+        let members = match (
+            schema_dict.get_item("enum")?,
+            schema_dict.get_item("const")?,
+        ) {
+            (Some(list), _) => list
+                .iter()?
+                .map(|member| py_to_json(member?))
+                .collect::<PyResult<Vec<_>>>()?,
+            (None, Some(value)) => vec![py_to_json(value)?],
+            (None, None) => return Ok(node),
+        };
+        if members.is_empty() {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "enum must not be empty",
+            ));
+        }
+        let inner = if schema_dict.get_item("type")?.is_some() {
+            node
+        } else {
+            infer_enum_type(&members)
+        };
+        Ok(SchemaNode::Enum {
+            inner: Arc::new(inner),
+            members,
+        })
+    }
+}
+
+/// 解析后校验的约束关键字；`"type": [...]` 拆出单个类型时去掉它们，由外层统一包装
+const CHECK_KEYS: &[&str] = &[
+    "minLength",
+    "maxLength",
+    "pattern",
+    "format",
+    "minimum",
+    "maximum",
+    "exclusiveMinimum",
+    "exclusiveMaximum",
+    "multipleOf",
+    "minItems",
+    "maxItems",
+    "uniqueItems",
+];
+
+fn compile_checks(schema_dict: &PyDict) -> PyResult<Vec<Check>> {
+    let mut checks = Vec::new();
+    if let Some(min) = schema_dict.get_item("minLength")? {
+        checks.push(Check::MinLength(min.extract()?));
+    }
+    if let Some(max) = schema_dict.get_item("maxLength")? {
+        checks.push(Check::MaxLength(max.extract()?));
+    }
+    if let Some(pattern) = schema_dict.get_item("pattern")? {
+        let source = pattern.extract::<&str>()?;
+        let regex = Regex::new(source).map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Invalid pattern {source:?}: {e}"
+            ))
+        })?;
+        checks.push(Check::Pattern(regex));
+    }
+    // 不认识的 format 只当注解，不校验
+    if let Some(format) = schema_dict.get_item("format")? {
+        if let Some(format) = Format::from_name(format.extract()?) {
+            checks.push(Check::Format(format));
+        }
+    }
+
+    // draft 4 的 exclusiveMinimum / exclusiveMaximum 是修饰 minimum / maximum 的布尔，
+    // 之后的版本改成了独立的数值界限，两种写法都认
+    type Bound = fn(f64) -> Check;
+    let bounds: [(&str, &str, Bound, Bound); 2] = [
+        (
+            "minimum",
+            "exclusiveMinimum",
+            Check::Minimum,
+            Check::ExclusiveMinimum,
+        ),
+        (
+            "maximum",
+            "exclusiveMaximum",
+            Check::Maximum,
+            Check::ExclusiveMaximum,
+        ),
+    ];
+    for (key, exclusive_key, inclusive, exclusive) in bounds {
+        let flag = schema_dict.get_item(exclusive_key)?;
+        let draft4 = match flag {
+            Some(flag) if flag.downcast::<PyBool>().is_ok() => Some(flag.is_true()?),
+            _ => None,
+        };
+        if let Some(limit) = schema_dict.get_item(key)? {
+            let limit = limit.extract::<f64>()?;
+            checks.push(if draft4 == Some(true) {
+                exclusive(limit)
+            } else {
+                inclusive(limit)
+            });
+        }
+        if let (Some(limit), None) = (flag, draft4) {
+            checks.push(exclusive(limit.extract()?));
+        }
+    }
+    if let Some(step) = schema_dict.get_item("multipleOf")? {
+        let step = step.extract::<f64>()?;
+        if !(step > 0.0 && step.is_finite()) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "multipleOf must be a positive number, got {step}"
+            )));
+        }
+        checks.push(Check::MultipleOf(step));
+    }
+
+    if let Some(min) = schema_dict.get_item("minItems")? {
+        checks.push(Check::MinItems(min.extract()?));
+    }
+    if let Some(max) = schema_dict.get_item("maxItems")? {
+        checks.push(Check::MaxItems(max.extract()?));
+    }
+    if let Some(unique) = schema_dict.get_item("uniqueItems")? {
+        if unique.is_true()? {
+            checks.push(Check::UniqueItems);
+        }
+    }
+    Ok(checks)
+}
+
+/// 没写 type 的 enum：按成员类型推断解析方式，混合类型退化为 Any
+fn infer_enum_type(members: &[JsonValue<'static>]) -> SchemaNode {
+    let all = |pred: fn(&JsonValue) -> bool| members.iter().filter(|m| !m.is_null()).all(pred);
+    let inner = if all(|m| matches!(m, JsonValue::String(_))) {
+        SchemaNode::PrimitiveString
+    } else if all(|m| matches!(m, JsonValue::Bool(_))) {
+        SchemaNode::PrimitiveBool
+    } else if all(|m| matches!(m, JsonValue::Number(Number::Int(_) | Number::BigInt(_)))) {
+        SchemaNode::PrimitiveInteger
+    } else if all(|m| matches!(m, JsonValue::Number(_))) {
+        SchemaNode::PrimitiveNumber
+    } else {
+        return SchemaNode::Any;
+    };
+    if members.iter().any(JsonValue::is_null) {
+        SchemaNode::Nullable(Arc::new(inner))
+    } else {
+        inner
+    }
+}
+
+/// Schema 里的字面量（enum 成员、const）转成与 Python 无关的 JsonValue
+fn py_to_json(obj: &PyAny) -> PyResult<JsonValue<'static>> {
+    if obj.is_none() {
+        Ok(JsonValue::Null)
+    } else if let Ok(b) = obj.downcast::<PyBool>() {
+        Ok(JsonValue::Bool(b.is_true()))
+    } else if let Ok(i) = obj.downcast::<PyLong>() {
+        Ok(JsonValue::Number(match i.extract::<i64>() {
+            Ok(small) => Number::Int(small),
+            Err(_) => Number::BigInt(i.str()?.to_string()),
+        }))
+    } else if let Ok(f) = obj.downcast::<PyFloat>() {
+        Ok(JsonValue::Number(Number::Float(f.value())))
+    } else if let Ok(s) = obj.downcast::<PyString>() {
+        Ok(JsonValue::String(Cow::Owned(s.to_str()?.to_string())))
+    } else if let Ok(list) = obj.downcast::<PyList>() {
+        let items = list.iter().map(py_to_json).collect::<PyResult<_>>()?;
+        Ok(JsonValue::Array(items))
+    } else if let Ok(dict) = obj.downcast::<PyDict>() {
+        let mut members = Vec::with_capacity(dict.len());
+        for (k, v) in dict {
+            members.push((Cow::Owned(k.extract::<String>()?), py_to_json(v)?));
+        }
+        Ok(JsonValue::Object(members))
+    } else {
+        Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Unsupported enum value: {}",
+            obj.repr()?
+        )))
+    }
+}
+
+/// 备选中该属性为字符串 const（单成员 enum）的，登记 取值 → 下标
+fn build_discriminator(
+    alternatives: &[Arc<SchemaNode>],
+    property: Vec<u8>,
+) -> Option<Discriminator> {
+    let mut mapping = AHashMap::new();
+    for (idx, alt) in alternatives.iter().enumerate() {
+        if let Some(tag) = const_tag(alt, &property) {
+            mapping.entry(tag).or_insert(idx);
+        }
+    }
+    (!mapping.is_empty()).then_some(Discriminator { property, mapping })
+}
+
+/// 自动探测：每个备选都是对象，且都把同一个属性定义为互不相同的字符串 const
+fn detect_discriminator(alternatives: &[Arc<SchemaNode>]) -> Option<Discriminator> {
+    let SchemaNode::Object { fields, .. } = alternatives.first()?.as_ref() else {
+        return None;
+    };
+    fields.keys().into_iter().find_map(|property| {
+        let found = build_discriminator(alternatives, property.to_vec())?;
+        (found.mapping.len() == alternatives.len()).then_some(found)
+    })
+}
+
+fn const_tag(node: &SchemaNode, property: &[u8]) -> Option<String> {
+    let SchemaNode::Object { fields, .. } = node else {
+        return None;
+    };
+    match fields.get(property)?.as_ref() {
+        SchemaNode::Enum { members, .. } if members.len() == 1 => {
+            members[0].as_str().map(str::to_string)
+        }
+        _ => None,
+    }
+}
+
+impl<'py> Compiler<'py> {
+    /// anyOf / oneOf：编译各个备选，并尝试找出判别字段
+    fn compile_union(
+        &mut self,
+        schema_dict: &'py PyDict,
+        alternatives: &'py PyAny,
+    ) -> PyResult<SchemaNode> {
+        let alternatives = alternatives
+            .iter()?
+            .map(|alt| self.compile(alt?))
+            .collect::<PyResult<Vec<_>>>()?;
+        if alternatives.is_empty() {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "anyOf / oneOf must not be empty",
+            ));
+        }
+
+        // OpenAPI 风格的 {"discriminator": {"propertyName": "kind"}}，没写就自动探测
+        let explicit = match schema_dict.get_item("discriminator")? {
+            Some(d) => match d.downcast::<PyDict>()?.get_item("propertyName")? {
+                Some(name) => Some(name.extract::<String>()?.into_bytes()),
+                None => None,
+            },
+            None => None,
+        };
+        let discriminator = match explicit {
+            Some(property) => build_discriminator(&alternatives, property),
+            None => detect_discriminator(&alternatives),
+        };
+
+        Ok(SchemaNode::Union {
+            alternatives,
+            discriminator,
+        })
+    }
+
+    /// 数组：没有前缀时是普通的 Array，否则是逐位置的 Tuple
+    fn compile_array(
+        &mut self,
+        prefix: Option<&'py PyAny>,
+        rest: Option<&'py PyAny>,
+    ) -> PyResult<SchemaNode> {
+        let Some(prefix) = prefix else {
+            let items = rest.ok_or_else(|| {
+                PyErr::new::<pyo3::exceptions::PyValueError, _>("Array schema missing 'items'")
+            })?;
+            return Ok(SchemaNode::Array(self.compile(items)?));
+        };
+
+        let prefix = prefix
+            .iter()?
+            .map(|item| self.compile(item?))
+            .collect::<PyResult<Vec<_>>>()?;
+        // items 缺省或为 true 时其余元素不受约束；为 false 时不允许超出前缀
+        let (items, closed) = match rest {
+            Some(flag) if flag.downcast::<PyBool>().is_ok() => {
+                (Arc::new(SchemaNode::Any), !flag.is_true()?)
+            }
+            Some(items) => (self.compile(items)?, false),
+            None => (Arc::new(SchemaNode::Any), false),
+        };
+        let len = prefix.len();
+        let tuple = SchemaNode::Tuple { prefix, items };
+        Ok(if closed {
+            SchemaNode::Checked {
+                inner: Arc::new(tuple),
+                checks: vec![Check::MaxItems(len)],
+            }
+        } else {
+            tuple
+        })
+    }
+
+    fn compile_type(&mut self, schema_obj: &'py PyAny) -> PyResult<SchemaNode> {
+        if let Ok(schema_dict) = schema_obj.downcast::<PyDict>() {
+            if let Some(alternatives) = schema_dict
+                .get_item("anyOf")?
+                .or(schema_dict.get_item("oneOf")?)
+            {
+                return self.compile_union(schema_dict, alternatives);
+            }
+
+            let type_val = schema_dict.get_item("type")?;
+
+            if let Some(t) = type_val {
+                // "type": ["string", "null"] 这类联合：剥掉 null，其余部分包成 Nullable
+                if let Ok(types) = t.downcast::<PyList>() {
+                    let mut names = Vec::with_capacity(types.len());
+                    for item in types {
+                        names.push(item.extract::<String>()?);
+                    }
+                    let nullable = names.iter().any(|n| n == "null");
+                    names.retain(|n| n != "null");
+                    let inner = match names.as_slice() {
+                        [] => return Ok(SchemaNode::PrimitiveNull),
+                        [single] => {
+                            // enum / const 与约束由外层统一包装，这里去掉以免校验两次
+                            let single_type = PyDict::new(schema_obj.py());
+                            for (k, v) in schema_dict {
+                                let key = k.extract::<&str>()?;
+                                if key != "enum" && key != "const" && !CHECK_KEYS.contains(&key) {
+                                    single_type.set_item(k, v)?;
                                 }
                             }
+                            single_type.set_item("type", single)?;
+                            self.compile_inline(single_type)?
+                        }
+                        _ => {
+                            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                                "Unsupported type union: {:?}",
+                                names
+                            )))
                         }
+                    };
+                    return Ok(if nullable {
+                        SchemaNode::Nullable(Arc::new(inner))
+                    } else {
+                        inner
+                    });
+                }
+
+                let type_str = t.extract::<String>()?;
+                match type_str.as_str() {
+                    "string" => Ok(SchemaNode::PrimitiveString),
+                    "number" => Ok(SchemaNode::PrimitiveNumber),
+                    "integer" => Ok(SchemaNode::PrimitiveInteger),
+                    "boolean" => Ok(SchemaNode::PrimitiveBool),
+                    "null" => Ok(SchemaNode::PrimitiveNull),
+                    "array" => {
+                        // 2020-12 的 prefixItems + items，或更早草案里列表形式的 items + additionalItems
+                        let items = schema_dict.get_item("items")?;
+                        let (prefix, rest) = match (schema_dict.get_item("prefixItems")?, items) {
+                            (Some(prefix), rest) => (Some(prefix), rest),
+                            (None, Some(list)) if list.downcast::<PyList>().is_ok() => {
+                                (Some(list), schema_dict.get_item("additionalItems")?)
+                            }
+                            (None, rest) => (None, rest),
+                        };
+                        self.containers += 1;
+                        let node = self.compile_array(prefix, rest);
+                        self.containers -= 1;
+                        node
                     }
+                    "object" => {
+                        let properties = schema_dict.get_item("properties")?;
+                        let required_list = schema_dict.get_item("required")?;
+
+                        let mut fields_vec = SmallVec::new();
+                        let mut fields_map = AHashMap::new();
+                        let mut patterns = Vec::new();
+                        let mut required_set = AHashSet::new();
+
+                        if let Some(props) = properties {
+                            if let Ok(props_dict) = props.downcast::<PyDict>() {
+                                for (k, v) in props_dict {
+                                    let key_str = k.extract::<String>()?;
+                                    let key_bytes = key_str.as_bytes().to_vec();
+                                    self.containers += 1;
+                                    let node = self.compile(v);
+                                    self.containers -= 1;
+                                    let node = node?;
 
-                    if let Some(req) = required_list {
-                        if let Ok(req_list) = req.downcast::<PyList>() {
-                            for item in req_list {
-                                let req_str = item.extract::<String>()?;
-                                required_set.insert(req_str.as_bytes().to_vec());
+                                    // 构建 Aho-Corasick 模式
+                                    // 1. 双引号: "key"
+                                    let mut dq = Vec::with_capacity(key_bytes.len() + 2);
+                                    dq.push(b'"');
+                                    dq.extend_from_slice(&key_bytes);
+                                    dq.push(b'"');
+                                    patterns.push(dq);
+
+                                    // 2. 单引号: 'key'
+                                    let mut sq = Vec::with_capacity(key_bytes.len() + 2);
+                                    sq.push(b'\'');
+                                    sq.extend_from_slice(&key_bytes);
+                                    sq.push(b'\'');
+                                    patterns.push(sq);
+
+                                    if props_dict.len() < SMALL_MAP_THRESHOLD {
+                                        fields_vec.push((key_bytes.clone(), node.clone()));
+                                    } else {
+                                        fields_map.insert(key_bytes.clone(), node.clone());
+                                    }
+                                }
                             }
                         }
-                    }
 
-                    let fields = if fields_map.is_empty() && !fields_vec.is_empty() {
-                        FieldLookup::Small(fields_vec)
-                    } else {
-                        FieldLookup::Large(fields_map)
-                    };
+                        if let Some(req) = required_list {
+                            if let Ok(req_list) = req.downcast::<PyList>() {
+                                for item in req_list {
+                                    let req_str = item.extract::<String>()?;
+                                    required_set.insert(req_str.as_bytes().to_vec());
+                                }
+                            }
+                        }
+
+                        let fields = if fields_map.is_empty() && !fields_vec.is_empty() {
+                            FieldLookup::Small(fields_vec)
+                        } else {
+                            FieldLookup::Large(fields_map)
+                        };
 
-                    // 构建 AC 自动机
-                    let ac = aho_corasick::AhoCorasick::new(&patterns).map_err(|e| {
-                        PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
-                            "Failed to build Aho-Corasick automaton: {}",
-                            e
-                        ))
-                    })?;
-
-                    Ok(SchemaNode::Object {
-                        fields,
-                        required: required_set,
-                        ac: Arc::new(ac),
-                    })
+                        // 构建 AC 自动机
+                        let ac = aho_corasick::AhoCorasick::new(&patterns).map_err(|e| {
+                            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                                "Failed to build Aho-Corasick automaton: {}",
+                                e
+                            ))
+                        })?;
+
+                        // 缺省与 false 一样：只认 properties 里的键
+                        let additional = match schema_dict.get_item("additionalProperties")? {
+                            Some(flag) if flag.downcast::<PyBool>().is_ok() => {
+                                flag.is_true()?.then(|| Arc::new(SchemaNode::Any))
+                            }
+                            Some(sub_schema) => {
+                                self.containers += 1;
+                                let node = self.compile(sub_schema);
+                                self.containers -= 1;
+                                Some(node?)
+                            }
+                            None => None,
+                        };
+
+                        Ok(SchemaNode::Object {
+                            fields,
+                            required: required_set,
+                            ac: Arc::new(ac),
+                            additional,
+                        })
+                    }
+                    _ => Ok(SchemaNode::Any),
                 }
-                _ => Ok(SchemaNode::Any),
+            } else {
+                // No type specified, assume Any
+                Ok(SchemaNode::Any)
             }
         } else {
-            // No type specified, assume Any
+            // Not a dict, maybe a string (primitive type shorthand)?
+            // For now, just return Any
             Ok(SchemaNode::Any)
         }
-    } else {
-        // Not a dict, maybe a string (primitive type shorthand)?
-        // For now, just return Any
-        Ok(SchemaNode::Any)
     }
 }
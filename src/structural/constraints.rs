@@ -0,0 +1,225 @@
+#[cfg(feature = "python")]
+use regex::Regex;
+
+/// 解析成功后再校验的 Schema 约束；只作用于对应类型的值，null 一律放行
+#[derive(Debug, Clone)]
+pub enum Check {
+    /// 字符串最少字符数（按 Unicode 码点计，与 JSON Schema 一致）
+    MinLength(usize),
+    MaxLength(usize),
+    /// 非锚定匹配：`"pattern": "^a"` 需要自己写 `^`；正则依赖随 `python` feature 引入
+    #[cfg(feature = "python")]
+    Pattern(Regex),
+    Format(Format),
+    Minimum(f64),
+    Maximum(f64),
+    ExclusiveMinimum(f64),
+    ExclusiveMaximum(f64),
+    /// 大于 0；浮点按相对误差判断，`0.3` 是 `0.1` 的倍数
+    MultipleOf(f64),
+    MinItems(usize),
+    MaxItems(usize),
+    /// 元素两两不同；与 JSON 一致，`true` 和 `1` 算不同的值
+    UniqueItems,
+}
+
+impl Check {
+    /// 字符串是否满足约束；数值约束不作用于字符串
+    pub fn accepts_str(&self, s: &str) -> bool {
+        match self {
+            Check::MinLength(min) => s.chars().count() >= *min,
+            Check::MaxLength(max) => s.chars().count() <= *max,
+            #[cfg(feature = "python")]
+            Check::Pattern(re) => re.is_match(s),
+            Check::Format(format) => format.matches(s),
+            _ => true,
+        }
+    }
+
+    /// 数值是否满足约束；字符串约束不作用于数值
+    pub fn accepts_number(&self, n: f64) -> bool {
+        match self {
+            Check::Minimum(min) => n >= *min,
+            Check::Maximum(max) => n <= *max,
+            Check::ExclusiveMinimum(min) => n > *min,
+            Check::ExclusiveMaximum(max) => n < *max,
+            Check::MultipleOf(step) => {
+                let quotient = n / step;
+                quotient.is_finite()
+                    && (quotient - quotient.round()).abs() <= 1e-9 * quotient.abs().max(1.0)
+            }
+            _ => true,
+        }
+    }
+
+    /// 数组是否满足约束；`all_unique` 只在有 uniqueItems 时才计算
+    pub fn accepts_array(&self, len: usize, all_unique: impl Fn() -> bool) -> bool {
+        match self {
+            Check::MinItems(min) => len >= *min,
+            Check::MaxItems(max) => len <= *max,
+            Check::UniqueItems => all_unique(),
+            _ => true,
+        }
+    }
+
+    /// 报错时的“期望”：`minLength 3`、`format "email"` 这类写法，与 Schema 原文对应
+    pub fn describe(&self) -> String {
+        match self {
+            Check::MinLength(min) => format!("minLength {min}"),
+            Check::MaxLength(max) => format!("maxLength {max}"),
+            // 正则原样展示，不做转义，方便与 Schema 对照
+            #[cfg(feature = "python")]
+            Check::Pattern(re) => format!("pattern \"{}\"", re.as_str()),
+            Check::Format(format) => format!("format {:?}", format.name()),
+            Check::Minimum(min) => format!("minimum {min}"),
+            Check::Maximum(max) => format!("maximum {max}"),
+            Check::ExclusiveMinimum(min) => format!("exclusiveMinimum {min}"),
+            Check::ExclusiveMaximum(max) => format!("exclusiveMaximum {max}"),
+            Check::MultipleOf(step) => format!("multipleOf {step}"),
+            Check::MinItems(min) => format!("minItems {min}"),
+            Check::MaxItems(max) => format!("maxItems {max}"),
+            Check::UniqueItems => "uniqueItems".to_string(),
+        }
+    }
+}
+
+/// 支持校验的 `format`；其余取值按 JSON Schema 的惯例只当注解
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Date,
+    DateTime,
+    Email,
+    Uri,
+    Uuid,
+}
+
+impl Format {
+    pub fn from_name(name: &str) -> Option<Format> {
+        match name {
+            "date" => Some(Format::Date),
+            "date-time" => Some(Format::DateTime),
+            "email" => Some(Format::Email),
+            "uri" => Some(Format::Uri),
+            "uuid" => Some(Format::Uuid),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Format::Date => "date",
+            Format::DateTime => "date-time",
+            Format::Email => "email",
+            Format::Uri => "uri",
+            Format::Uuid => "uuid",
+        }
+    }
+
+    pub fn matches(self, s: &str) -> bool {
+        match self {
+            Format::Date => is_date(s.as_bytes()),
+            Format::DateTime => is_date_time(s.as_bytes()),
+            Format::Email => is_email(s),
+            Format::Uri => is_uri(s),
+            Format::Uuid => is_uuid(s.as_bytes()),
+        }
+    }
+}
+
+/// 固定宽度的十进制数字
+fn digits(s: &[u8]) -> Option<u32> {
+    if s.is_empty() || !s.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    Some(s.iter().fold(0, |n, &b| n * 10 + u32::from(b - b'0')))
+}
+
+/// RFC 3339 full-date：YYYY-MM-DD，日期须真实存在
+fn is_date(s: &[u8]) -> bool {
+    if s.len() != 10 || s[4] != b'-' || s[7] != b'-' {
+        return false;
+    }
+    let year = digits(&s[..4]);
+    let (Some(year), Some(month), Some(day)) = (year, digits(&s[5..7]), digits(&s[8..])) else {
+        return false;
+    };
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return false,
+    };
+    (1..=days).contains(&day)
+}
+
+/// RFC 3339 date-time：日期 `T` 时间，秒可带小数，必须有 `Z` 或 `±HH:MM` 时区
+fn is_date_time(s: &[u8]) -> bool {
+    if s.len() < 20 || !is_date(&s[..10]) || !matches!(s[10], b'T' | b't' | b' ') {
+        return false;
+    }
+    let time = &s[11..];
+    if time.len() < 9 || time[2] != b':' || time[5] != b':' {
+        return false;
+    }
+    let (Some(hour), Some(minute), Some(second)) =
+        (digits(&time[..2]), digits(&time[3..5]), digits(&time[6..8]))
+    else {
+        return false;
+    };
+    // 60 留给闰秒
+    if hour > 23 || minute > 59 || second > 60 {
+        return false;
+    }
+
+    let mut rest = &time[8..];
+    if let Some(frac) = rest.strip_prefix(b".") {
+        let len = frac.iter().take_while(|b| b.is_ascii_digit()).count();
+        if len == 0 {
+            return false;
+        }
+        rest = &frac[len..];
+    }
+    match rest {
+        [b'Z' | b'z'] => true,
+        [b'+' | b'-', h1, h2, b':', m1, m2] => {
+            let offset = (digits(&[*h1, *h2]), digits(&[*m1, *m2]));
+            matches!(offset, (Some(h), Some(m)) if h <= 23 && m <= 59)
+        }
+        _ => false,
+    }
+}
+
+/// 实用主义的邮箱检查：恰好一个 `@`，两侧非空且无空白，域名至少含一个点且各段非空
+fn is_email(s: &str) -> bool {
+    let Some((local, domain)) = s.split_once('@') else {
+        return false;
+    };
+    !local.is_empty()
+        && !domain.contains('@')
+        && !s.chars().any(char::is_whitespace)
+        && domain.contains('.')
+        && domain.split('.').all(|label| !label.is_empty())
+}
+
+/// 绝对 URI：`scheme:` 开头，scheme 以字母起始，后面还有内容，整体不含空白
+fn is_uri(s: &str) -> bool {
+    let Some((scheme, rest)) = s.split_once(':') else {
+        return false;
+    };
+    let mut chars = scheme.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        && !rest.is_empty()
+        && !s.chars().any(|c| c.is_whitespace() || c.is_control())
+}
+
+/// 8-4-4-4-12 的十六进制
+fn is_uuid(s: &[u8]) -> bool {
+    s.len() == 36
+        && s.iter().enumerate().all(|(i, b)| match i {
+            8 | 13 | 18 | 23 => *b == b'-',
+            _ => b.is_ascii_hexdigit(),
+        })
+}
@@ -0,0 +1,109 @@
+use super::parser::ParseError;
+use pyo3::PyObject;
+
+/// 联合类型的一个备选从某个位置试探解析的结果：(值, 结束位置) 或错误，以及期间记录的字段位置
+pub(crate) type Trial = (Result<(PyObject, usize), ParseError>, Vec<FieldSpan>);
+
+/// 一个字段在原文中的字节区间，`pointer` 为 RFC 6901 JSON Pointer（根为空串）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldSpan {
+    pub pointer: String,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// 单次提取的解析状态：嵌套深度、当前路径，以及按需收集的字段位置表
+#[derive(Debug, Default)]
+pub struct ParseContext {
+    pub(crate) depth: usize,
+    path: Vec<String>,
+    spans: Option<Vec<FieldSpan>>,
+    /// enum 比较时忽略大小写、首尾空白和全角差异
+    pub(crate) fuzzy_enums: bool,
+    /// 当前值是数组元素：逗号只是分隔符，`[1,250]` 不能读成千分位
+    pub(crate) in_array: bool,
+    /// 已校验为合法 UTF-8 的字节区间 [起点, 终点)，`{}` 子树交给 repair 前复用
+    pub(crate) utf8_checked: Option<(usize, usize)>,
+    /// 按 (备选节点地址, 起始位置, in_array) 缓存的联合类型试探结果。递归的 anyOf 里，
+    /// 外层每个备选都会重新解析同一批子值，不缓存时耗时随嵌套层数指数增长
+    pub(crate) trials: ahash::AHashMap<(usize, usize, bool), Trial>,
+}
+
+impl ParseContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 额外记录每个解析出的值的位置
+    pub fn with_spans() -> Self {
+        ParseContext {
+            spans: Some(Vec::new()),
+            ..Self::default()
+        }
+    }
+
+    /// 开启 enum 的宽松匹配，命中时返回规范的成员值
+    pub fn with_fuzzy_enums(mut self, enabled: bool) -> Self {
+        self.fuzzy_enums = enabled;
+        self
+    }
+
+    /// 进入子节点：对象的键或数组下标
+    pub(crate) fn enter(&mut self, segment: impl FnOnce() -> String) {
+        self.depth += 1;
+        // 不收集位置时路径用不到，省掉分配
+        if self.spans.is_some() {
+            self.path.push(segment());
+        }
+    }
+
+    pub(crate) fn leave(&mut self) {
+        self.depth -= 1;
+        self.path.pop();
+    }
+
+    /// 试探性解析前的位置标记，配合 `take_spans_since` 撤回这段期间记录的位置
+    pub(crate) fn span_mark(&self) -> usize {
+        self.spans.as_ref().map_or(0, Vec::len)
+    }
+
+    pub(crate) fn take_spans_since(&mut self, mark: usize) -> Vec<FieldSpan> {
+        self.spans
+            .as_mut()
+            .map_or_else(Vec::new, |spans| spans.split_off(mark))
+    }
+
+    pub(crate) fn restore_spans(&mut self, taken: Vec<FieldSpan>) {
+        if let Some(spans) = &mut self.spans {
+            spans.extend(taken);
+        }
+    }
+
+    pub(crate) fn record_span(&mut self, start: usize, end: usize) {
+        if let Some(spans) = &mut self.spans {
+            spans.push(FieldSpan {
+                pointer: pointer(&self.path),
+                start,
+                end,
+            });
+        }
+    }
+
+    /// 收集到的位置，按起点排序（父节点在子节点之前）；未开启时为 `None`
+    pub fn into_spans(self) -> Option<Vec<FieldSpan>> {
+        self.spans.map(|mut spans| {
+            spans.sort_by_key(|s| s.start);
+            spans
+        })
+    }
+}
+
+/// RFC 6901：`~` 写作 `~0`，`/` 写作 `~1`
+pub(crate) fn pointer(path: &[String]) -> String {
+    let mut out = String::new();
+    for segment in path {
+        out.push('/');
+        out.push_str(&segment.replace('~', "~0").replace('/', "~1"));
+    }
+    out
+}
@@ -1,3 +1,8 @@
-pub mod schema;
-pub mod parser;
+#[cfg(feature = "python")]
 pub mod compiler;
+pub mod constraints;
+#[cfg(feature = "python")]
+pub mod context;
+#[cfg(feature = "python")]
+pub mod parser;
+pub mod schema;
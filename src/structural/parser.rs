@@ -1,28 +1,115 @@
-use super::schema::{FieldLookup, SchemaNode};
+use super::constraints::Check;
+use super::context::{pointer, ParseContext};
+use super::schema::{Discriminator, FieldLookup, RefSlot, SchemaNode};
+use crate::repair::{self, JsonValue, RepairErrorKind, RepairOptions};
 use crate::utils::cursor::Cursor;
+use crate::utils::escape;
 use pyo3::prelude::*;
-use pyo3::types::{PyBool, PyDict, PyFloat, PyList, PyString};
+use pyo3::types::{PyBool, PyDict, PyFloat, PyList, PyLong, PyString};
 use std::fmt;
+use std::sync::{Arc, Weak};
 
-#[derive(Debug)]
+/// 提取失败原因，偏移均为 Cursor 输入中的字节位置
+#[derive(Debug, Clone)]
 pub enum ParseError {
-    RecursionLimit,
-    MissingField(String),
-    InvalidUtf8,
-    UnexpectedEof,
+    RecursionLimit {
+        offset: usize,
+    },
+    MissingField {
+        field: String,
+        offset: usize,
+    },
+    InvalidUtf8 {
+        offset: usize,
+    },
+    UnexpectedEof {
+        offset: usize,
+    },
+    /// 整数字段的值不是整数（有非零小数部分，或根本不是数字）
+    InvalidInteger {
+        literal: String,
+        offset: usize,
+    },
+    /// 值与 Schema 类型对不上，例如布尔字段写成了 `maybe`
+    TypeMismatch {
+        expected: &'static [&'static str],
+        offset: usize,
+    },
+    /// 值不在 enum / const 允许的范围内；allowed 为各成员的 JSON 写法
+    NotInEnum {
+        found: String,
+        allowed: Vec<String>,
+        offset: usize,
+    },
+    /// 值解析成功但违反 minLength / pattern / format 等约束；path 为 JSON Pointer 的各段
+    ConstraintViolation {
+        path: Vec<String>,
+        expected: String,
+        offset: usize,
+    },
+    /// `{}` 子树按 repair 的宽松规则也解析不了
+    InvalidValue {
+        message: String,
+        expected: &'static [&'static str],
+        offset: usize,
+    },
+}
+
+impl ParseError {
+    pub fn offset(&self) -> usize {
+        match self {
+            ParseError::RecursionLimit { offset }
+            | ParseError::MissingField { offset, .. }
+            | ParseError::InvalidUtf8 { offset }
+            | ParseError::UnexpectedEof { offset }
+            | ParseError::InvalidInteger { offset, .. }
+            | ParseError::TypeMismatch { offset, .. }
+            | ParseError::NotInEnum { offset, .. }
+            | ParseError::ConstraintViolation { offset, .. }
+            | ParseError::InvalidValue { offset, .. } => *offset,
+        }
+    }
+
+    /// 违反约束的值所在的 JSON Pointer；其他错误没有路径
+    pub fn path(&self) -> Option<String> {
+        match self {
+            ParseError::ConstraintViolation { path, .. } => Some(pointer(path)),
+            _ => None,
+        }
+    }
+
+    /// 错误从字段或数组元素里冒出来时，补上这一层的路径段
+    fn within(mut self, segment: impl FnOnce() -> String) -> Self {
+        if let ParseError::ConstraintViolation { path, .. } = &mut self {
+            path.insert(0, segment());
+        }
+        self
+    }
+
+    /// 失败位置上本应出现的记号
+    pub fn expected(&self) -> Vec<String> {
+        match self {
+            ParseError::MissingField { field, .. } => vec![format!("{field:?}")],
+            ParseError::UnexpectedEof { .. } => vec!["closing quote".to_string()],
+            ParseError::InvalidInteger { .. } => vec!["an integer".to_string()],
+            ParseError::TypeMismatch { expected, .. }
+            | ParseError::InvalidValue { expected, .. } => {
+                expected.iter().map(|s| s.to_string()).collect()
+            }
+            ParseError::NotInEnum { allowed, .. } => allowed.clone(),
+            ParseError::ConstraintViolation { expected, .. } => vec![expected.clone()],
+            _ => Vec::new(),
+        }
+    }
 }
 
 impl From<ParseError> for PyErr {
     fn from(err: ParseError) -> PyErr {
         match err {
-            ParseError::RecursionLimit => {
-                pyo3::exceptions::PyRecursionError::new_err("Recursion limit reached")
-            }
-            ParseError::MissingField(f) => {
-                pyo3::exceptions::PyValueError::new_err(format!("Missing field: {}", f))
+            ParseError::RecursionLimit { .. } => {
+                pyo3::exceptions::PyRecursionError::new_err(err.to_string())
             }
-            ParseError::InvalidUtf8 => pyo3::exceptions::PyValueError::new_err("Invalid UTF-8"),
-            ParseError::UnexpectedEof => pyo3::exceptions::PyValueError::new_err("Unexpected EOF"),
+            _ => pyo3::exceptions::PyValueError::new_err(err.to_string()),
         }
     }
 }
@@ -30,16 +117,175 @@ impl From<ParseError> for PyErr {
 impl fmt::Display for ParseError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            ParseError::RecursionLimit => write!(f, "Recursion limit reached"),
-            ParseError::MissingField(field) => write!(f, "Missing field: {}", field),
-            ParseError::InvalidUtf8 => write!(f, "Invalid UTF-8"),
-            ParseError::UnexpectedEof => write!(f, "Unexpected EOF"),
+            ParseError::RecursionLimit { .. } => write!(f, "Recursion limit reached"),
+            ParseError::MissingField { field, .. } => write!(f, "Missing field: {}", field),
+            ParseError::InvalidUtf8 { .. } => write!(f, "Invalid UTF-8"),
+            ParseError::UnexpectedEof { .. } => write!(f, "Unexpected EOF"),
+            ParseError::InvalidInteger { literal, .. } => {
+                write!(f, "Invalid integer: {:?}", literal)
+            }
+            ParseError::TypeMismatch { expected, .. } => {
+                write!(f, "Unexpected value, expected {}", expected.join(" or "))
+            }
+            ParseError::NotInEnum { found, allowed, .. } => {
+                write!(f, "Value {} is not one of {}", found, allowed.join(", "))
+            }
+            ParseError::ConstraintViolation { path, expected, .. } => {
+                if path.is_empty() {
+                    write!(f, "Value violates {}", expected)
+                } else {
+                    write!(f, "Value at {} violates {}", pointer(path), expected)
+                }
+            }
+            ParseError::InvalidValue { message, .. } => write!(f, "{}", message),
         }
     }
 }
 
 const MAX_DEPTH: usize = 128;
 const MAX_STRING_LEN: usize = 1024 * 1024; // 1MB
+/// 整数展开后的最大位数，与 CPython 默认的 int 字符串转换上限一致
+const MAX_INTEGER_DIGITS: usize = 4300;
+/// 对象没有闭合（输入被截断）时，每次向前搜索键的最大字节数
+const KEY_SEARCH_WINDOW: usize = 64 * 1024;
+
+/// 在 `text[from..]` 中寻找根节点可能的起点：对象找 '{'，数组找 '['，
+/// 标量找引号 / 数字 / true / false，且必须在词首（`untrue`、`v2` 里的不算），
+/// 也不能落在更长的数字中间（`3.5` 的 `5`、`1e-5` 的 `5`）
+pub fn find_root_start(schema: &SchemaNode, text: &[u8], from: usize) -> Option<usize> {
+    let haystack = &text[from..];
+    let idx = match schema {
+        SchemaNode::Object { .. } => memchr::memchr(b'{', haystack),
+        SchemaNode::Array(_) | SchemaNode::Tuple { .. } => memchr::memchr(b'[', haystack),
+        SchemaNode::Any => memchr::memchr2(b'{', b'[', haystack),
+        _ => (0..haystack.len()).find(|&i| {
+            let at = from + i;
+            (at == 0 || !continues_word(&text[at - 1..at]))
+                && !continues_number(&text[..at])
+                && starts_value(schema, &haystack[i..])
+        }),
+    };
+    idx.map(|i| from + i)
+}
+
+/// 被拒候选的结束位置：'{' / '[' 闭合之后，或数字记号（`-3.0`、`"12"`）之后；
+/// 其他标量或截断的容器返回 None
+pub fn candidate_end(text: &[u8], start: usize) -> Option<usize> {
+    if !matches!(text.get(start), Some(b'{' | b'[')) {
+        // 根节点不在数组里，与解析时一样容忍千分位
+        let (_, _, consumed) = number_token(&text[start..], true);
+        return (consumed > 0).then_some(start + consumed);
+    }
+    scope_end(&text[start + 1..]).map(|end| start + end + 2)
+}
+
+/// 找不到任何起点时，报告里“期望出现”的记号
+pub fn root_expected(schema: &SchemaNode) -> Vec<String> {
+    let expected: &[&str] = match schema {
+        SchemaNode::Object { .. } => &["'{'"],
+        SchemaNode::Array(_) | SchemaNode::Tuple { .. } => &["'['"],
+        SchemaNode::PrimitiveString => &["a string"],
+        SchemaNode::PrimitiveNumber => &["a number"],
+        SchemaNode::PrimitiveInteger => &["an integer"],
+        SchemaNode::PrimitiveBool => &["true", "false"],
+        SchemaNode::PrimitiveNull => &["null"],
+        SchemaNode::Nullable(inner) => {
+            let mut expected = root_expected(inner);
+            expected.push("null".to_string());
+            return expected;
+        }
+        SchemaNode::Enum { inner, .. } | SchemaNode::Checked { inner, .. } => {
+            return root_expected(inner)
+        }
+        SchemaNode::Ref(slot) => return root_expected(&ref_target(slot)),
+        SchemaNode::Union { alternatives, .. } => {
+            let mut expected: Vec<String> = Vec::new();
+            for alt in alternatives {
+                for token in root_expected(alt) {
+                    if !expected.contains(&token) {
+                        expected.push(token);
+                    }
+                }
+            }
+            return expected;
+        }
+        SchemaNode::Any => &["'{'", "'['"],
+    };
+    expected.iter().map(|s| s.to_string()).collect()
+}
+
+/// `rest` 开头是否可能是该 Schema 的值
+fn starts_value(schema: &SchemaNode, rest: &[u8]) -> bool {
+    match schema {
+        SchemaNode::Object { .. } => rest.starts_with(b"{"),
+        SchemaNode::Array(_) | SchemaNode::Tuple { .. } => rest.starts_with(b"["),
+        SchemaNode::Any => rest.starts_with(b"{") || rest.starts_with(b"["),
+        SchemaNode::PrimitiveNull => null_literal_len(rest).is_some(),
+        SchemaNode::Nullable(inner) => {
+            null_literal_len(rest).is_some() || starts_value(inner, rest)
+        }
+        SchemaNode::Enum { inner, .. } | SchemaNode::Checked { inner, .. } => {
+            starts_value(inner, rest)
+        }
+        SchemaNode::Ref(slot) => starts_value(&ref_target(slot), rest),
+        SchemaNode::Union { alternatives, .. } => {
+            alternatives.iter().any(|alt| starts_value(alt, rest))
+        }
+        SchemaNode::PrimitiveString => {
+            rest.starts_with(b"\"") || rest.starts_with(b"'") || rest.starts_with("＂".as_bytes())
+        }
+        SchemaNode::PrimitiveNumber | SchemaNode::PrimitiveInteger => {
+            // 符号或小数点后面必须紧跟数字，否则只是标点
+            let unsigned = rest
+                .strip_prefix(b"-")
+                .or_else(|| rest.strip_prefix(b"+"))
+                .unwrap_or(rest);
+            let digits = unsigned.strip_prefix(b".").unwrap_or(unsigned);
+            digits.first().is_some_and(u8::is_ascii_digit)
+        }
+        SchemaNode::PrimitiveBool => bool_literal(rest).is_some(),
+    }
+}
+
+/// 裸字面量 null / None / NULL 的长度；后面紧跟标识符字符（如 `nullable`）时不算
+fn null_literal_len(rest: &[u8]) -> Option<usize> {
+    [b"null".as_slice(), b"None", b"NULL"]
+        .iter()
+        .find(|word| rest.starts_with(word) && !continues_word(&rest[word.len()..]))
+        .map(|word| word.len())
+}
+
+/// 布尔字面量（含 Python 风格与带引号的写法）：返回 (值, 长度)
+fn bool_literal(rest: &[u8]) -> Option<(bool, usize)> {
+    const WORDS: [(&[u8], bool); 8] = [
+        (b"true", true),
+        (b"false", false),
+        (b"True", true),
+        (b"False", false),
+        (b"\"true\"", true),
+        (b"\"false\"", false),
+        (b"'true'", true),
+        (b"'false'", false),
+    ];
+    WORDS
+        .iter()
+        .find(|(word, _)| rest.starts_with(word) && !continues_word(&rest[word.len()..]))
+        .map(|(word, value)| (*value, word.len()))
+}
+
+/// `before` 是否以数字记号的中段结尾：`<数字>.`、`<数字>+` / `<数字>-`，或指数里的 `<数字>e-`
+fn continues_number(before: &[u8]) -> bool {
+    match before {
+        [.., d, b'e' | b'E', b'+' | b'-'] if d.is_ascii_digit() => true,
+        [.., d, b'.' | b'+' | b'-'] => d.is_ascii_digit(),
+        _ => false,
+    }
+}
+
+fn continues_word(rest: &[u8]) -> bool {
+    rest.first()
+        .is_some_and(|b| b.is_ascii_alphanumeric() || *b == b'_')
+}
 
 pub fn parse_node<'py>(
     cursor: &mut Cursor,
@@ -47,23 +293,475 @@ pub fn parse_node<'py>(
     py: Python<'py>,
     depth: usize,
 ) -> Result<PyObject, ParseError> {
-    if depth > MAX_DEPTH {
-        return Err(ParseError::RecursionLimit);
+    let mut ctx = ParseContext::new();
+    ctx.depth = depth;
+    parse_node_with(cursor, schema, py, &mut ctx)
+}
+
+/// 与 `parse_node` 相同，但状态放在调用方提供的上下文里（例如用来收集字段位置）
+pub fn parse_node_with<'py>(
+    cursor: &mut Cursor,
+    schema: &SchemaNode,
+    py: Python<'py>,
+    ctx: &mut ParseContext,
+) -> Result<PyObject, ParseError> {
+    if ctx.depth > MAX_DEPTH {
+        return Err(ParseError::RecursionLimit { offset: cursor.pos });
     }
     cursor.skip_whitespace();
+    let start = cursor.pos;
+
+    let value = parse_value(cursor, schema, py, ctx)?;
+
+    ctx.record_span(start, cursor.offset());
+    Ok(value)
+}
+
+/// 按节点类型分派；深度检查和位置记录由 `parse_node_with` 负责
+fn parse_value<'py>(
+    cursor: &mut Cursor,
+    schema: &SchemaNode,
+    py: Python<'py>,
+    ctx: &mut ParseContext,
+) -> Result<PyObject, ParseError> {
+    // 裸 null / None 只有可为 null 的类型才接受，其他标量报类型不符
+    // （带引号的 "null" 仍是字符串）
+    if let Some(len) = null_literal_len(cursor.remaining()) {
+        let expected: &'static [&'static str] = match schema {
+            SchemaNode::PrimitiveNull | SchemaNode::Nullable(_) => {
+                cursor.advance(len);
+                return Ok(py.None());
+            }
+            SchemaNode::PrimitiveString => &["a string"],
+            SchemaNode::PrimitiveNumber => &["a number"],
+            SchemaNode::PrimitiveInteger => &["an integer"],
+            SchemaNode::PrimitiveBool => &["true", "false"],
+            _ => &[],
+        };
+        if !expected.is_empty() {
+            return Err(ParseError::TypeMismatch {
+                expected,
+                offset: cursor.pos,
+            });
+        }
+    }
 
     match schema {
-        SchemaNode::PrimitiveString => parse_string_speculative(cursor, py),
-        SchemaNode::PrimitiveNumber => parse_number_robust(cursor, py),
+        SchemaNode::PrimitiveString => parse_string_speculative(cursor, py, ctx.depth == 0),
+        SchemaNode::PrimitiveNumber => parse_number_robust(cursor, py, !ctx.in_array),
+        SchemaNode::PrimitiveInteger => parse_integer_robust(cursor, py, !ctx.in_array),
         SchemaNode::PrimitiveBool => parse_bool_speculative(cursor, py),
+        SchemaNode::PrimitiveNull => Err(ParseError::TypeMismatch {
+            expected: &["null"],
+            offset: cursor.pos,
+        }),
+        SchemaNode::Nullable(inner) => parse_value(cursor, inner, py, ctx),
+        SchemaNode::Enum { inner, members } => parse_enum(cursor, inner, members, py, ctx),
+        SchemaNode::Ref(slot) => parse_value(cursor, &ref_target(slot), py, ctx),
+        SchemaNode::Checked { inner, checks } => {
+            let start = cursor.offset();
+            let value = parse_value(cursor, inner, py, ctx)?;
+            match violated(value.as_ref(py), checks) {
+                Some(check) => Err(ParseError::ConstraintViolation {
+                    path: Vec::new(),
+                    expected: check.describe(),
+                    offset: start,
+                }),
+                None => Ok(value),
+            }
+        }
+        SchemaNode::Union {
+            alternatives,
+            discriminator,
+        } => parse_union(cursor, alternatives, discriminator.as_ref(), py, ctx),
         SchemaNode::Object {
             fields,
             required,
             ac,
-        } => parse_object(cursor, fields, required, ac, py, depth),
-        SchemaNode::Array(inner) => parse_array(cursor, inner, py, depth),
-        _ => Ok(py.None().into()), // Placeholder for Any or unimplemented types
+            additional,
+        } => parse_object(cursor, fields, required, ac, additional.as_deref(), py, ctx),
+        SchemaNode::Array(inner) => parse_array(cursor, &[], inner, py, ctx),
+        SchemaNode::Tuple { prefix, items } => parse_array(cursor, prefix, items, py, ctx),
+        SchemaNode::Any => parse_any(cursor, py, ctx),
+    }
+}
+
+/// `{}` / 未知类型：不受 Schema 约束，按 repair_json 的宽松规则解析一个完整的值。
+/// 与提取器本身一样容忍未加引号的键和漏写的逗号。字段里 repair 也修不好的标量
+/// （`hello world`）按未加引号的字符串取一段，取不到就是 None；修不好的对象/数组整段跳过、
+/// 取 None。都不拖垮其他字段，只有根节点照常报错
+fn parse_any<'py>(
+    cursor: &mut Cursor,
+    py: Python<'py>,
+    ctx: &mut ParseContext,
+) -> Result<PyObject, ParseError> {
+    let base = cursor.offset();
+    let rest = cursor.remaining();
+    let text = utf8_prefix(rest, base, ctx);
+    let options = RepairOptions {
+        scan_prefix: false,
+        allow_unquoted_keys: true,
+        insert_missing_commas: true,
+        max_depth: MAX_DEPTH.saturating_sub(ctx.depth),
+        max_string_len: MAX_STRING_LEN,
+        ..RepairOptions::default()
+    };
+    let (value, end) = match repair::repair_value_at(text, 0, &options) {
+        Ok(repaired) => repaired,
+        Err(err) => {
+            let offset = base + err.offset();
+            return match err.kind() {
+                RepairErrorKind::DepthLimit(_) => Err(ParseError::RecursionLimit { offset }),
+                _ if ctx.depth == 0 => Err(ParseError::InvalidValue {
+                    message: err.message(),
+                    expected: err.expected(),
+                    offset,
+                }),
+                // 截断的容器一直延伸到输入结尾
+                _ if matches!(rest.first(), Some(b'{' | b'[')) => {
+                    cursor.advance(scope_end(&rest[1..]).map_or(rest.len(), |end| end + 2));
+                    Ok(py.None())
+                }
+                _ => {
+                    let start = cursor.pos;
+                    let value = parse_string_speculative(cursor, py, false)?;
+                    Ok(if cursor.pos > start { value } else { py.None() })
+                }
+            };
+        }
+    };
+    let obj = value.to_py(py).map_err(|err| ParseError::InvalidValue {
+        message: err.to_string(),
+        expected: &[],
+        offset: base,
+    })?;
+    cursor.advance(end);
+    Ok(obj)
+}
+
+/// `rest`（从输入的 `base` 处开始）里最长的合法 UTF-8 前缀，坏字节处自然截断。
+/// 校验过的区间记在 ctx 里，同一次提取后面的 `{}` 值直接复用，
+/// 否则数组里的每个值都要把余下的全部输入重新校验一遍
+fn utf8_prefix<'t>(rest: &'t [u8], base: usize, ctx: &mut ParseContext) -> &'t str {
+    // 起点落在多字节字符中间时不能复用
+    let on_boundary = rest.first().is_none_or(|&b| (b as i8) >= -0x40);
+    let valid_end = match ctx.utf8_checked {
+        Some((from, end)) if on_boundary && from <= base && base <= end => end,
+        _ => {
+            let valid = match std::str::from_utf8(rest) {
+                Ok(_) => rest.len(),
+                Err(err) => err.valid_up_to(),
+            };
+            ctx.utf8_checked = Some((base, base + valid));
+            base + valid
+        }
+    };
+    let valid = &rest[..(valid_end - base).min(rest.len())];
+    // 安全性：valid 位于已校验过的区间内，且从字符边界开始
+    unsafe { std::str::from_utf8_unchecked(valid) }
+}
+
+/// 第一条不满足的约束；约束只作用于对应类型的值（null、布尔等一律放行）
+fn violated<'c>(value: &PyAny, checks: &'c [Check]) -> Option<&'c Check> {
+    if let Ok(s) = value.downcast::<PyString>() {
+        let s = s.to_str().ok()?;
+        return checks.iter().find(|check| !check.accepts_str(s));
+    }
+    if let Ok(list) = value.downcast::<PyList>() {
+        let all_unique = || {
+            let items: Vec<&PyAny> = list.iter().collect();
+            items
+                .iter()
+                .enumerate()
+                .all(|(i, a)| items[..i].iter().all(|b| !same_value(a, b)))
+        };
+        return checks
+            .iter()
+            .find(|check| !check.accepts_array(list.len(), all_unique));
+    }
+    let numeric = value.is_instance_of::<PyFloat>()
+        || (value.is_instance_of::<PyLong>() && !value.is_instance_of::<PyBool>());
+    if !numeric {
+        return None;
+    }
+    // 超出 f64 范围的大整数按符号当作 ±inf，上下界照样生效
+    let n = value.extract::<f64>().unwrap_or_else(|_| {
+        if value.lt(0).unwrap_or(false) {
+            f64::NEG_INFINITY
+        } else {
+            f64::INFINITY
+        }
+    });
+    checks.iter().find(|check| !check.accepts_number(n))
+}
+
+/// 递归 `$ref` 的目标；编译结束时已回填，且由外层节点持有
+fn ref_target(slot: &RefSlot) -> Arc<SchemaNode> {
+    slot.get()
+        .and_then(Weak::upgrade)
+        .expect("$ref target is filled in by the compiler")
+}
+
+/// 联合类型：判别字段命中时直接按对应备选解析；否则逐个试探，
+/// 取锚定字段最多的结果（同分时取消耗输入更多的，再同分取靠前的）。
+/// 开头就对得上的备选优先（`42` 不让给未加引号的字符串，`null` 不让给对象），
+/// 它们都失败时才退回其余不需要括号开头的备选
+fn parse_union<'py>(
+    cursor: &mut Cursor,
+    alternatives: &[Arc<SchemaNode>],
+    discriminator: Option<&Discriminator>,
+    py: Python<'py>,
+    ctx: &mut ParseContext,
+) -> Result<PyObject, ParseError> {
+    if let Some(idx) = discriminator.and_then(|d| dispatch(cursor.remaining(), d, ctx)) {
+        return parse_value(cursor, &alternatives[idx], py, ctx);
+    }
+
+    let rest = cursor.remaining().trim_ascii_start();
+    let (viable, fallback): (Vec<_>, Vec<_>) =
+        alternatives.iter().partition(|alt| starts_value(alt, rest));
+    let mut fallback: Vec<_> = fallback
+        .into_iter()
+        .filter(|alt| !needs_bracket(alt))
+        .collect();
+    if viable.is_empty() && fallback.is_empty() {
+        // 没有一个对得上：照常逐个试，让报错来自最接近的备选
+        fallback = alternatives.iter().collect();
+    }
+
+    let mark = ctx.span_mark();
+    let mut best: Option<((usize, usize), Cursor, PyObject, Vec<_>)> = None;
+    let mut furthest: Option<ParseError> = None;
+    for group in [viable, fallback] {
+        for alt in group {
+            let mut trial = *cursor;
+            let key = (Arc::as_ptr(alt) as usize, cursor.pos, ctx.in_array);
+            let (result, spans) = match ctx.trials.get(&key) {
+                Some(cached) => cached.clone(),
+                None => {
+                    let result = parse_value(&mut trial, alt, py, ctx).map(|v| (v, trial.pos));
+                    let spans = ctx.take_spans_since(mark);
+                    ctx.trials.insert(key, (result.clone(), spans.clone()));
+                    (result, spans)
+                }
+            };
+            match result {
+                Ok((value, end)) => {
+                    trial.pos = end;
+                    let anchored = value.downcast::<PyDict>(py).map_or(0, |dict| dict.len());
+                    let score = (anchored, trial.pos);
+                    if best.as_ref().is_none_or(|(top, ..)| score > *top) {
+                        best = Some((score, trial, value, spans));
+                    }
+                }
+                Err(err) => {
+                    if furthest.as_ref().is_none_or(|f| err.offset() > f.offset()) {
+                        furthest = Some(err);
+                    }
+                }
+            }
+        }
+        if best.is_some() {
+            break;
+        }
+    }
+
+    match best {
+        Some((_, trial, value, spans)) => {
+            *cursor = trial;
+            ctx.restore_spans(spans);
+            Ok(value)
+        }
+        None => Err(furthest.expect("union has at least one alternative")),
+    }
+}
+
+/// 只能以 '{' / '[' 开头的 Schema（对象、数组），开头对不上时不拿来兜底
+fn needs_bracket(schema: &SchemaNode) -> bool {
+    match schema {
+        SchemaNode::Object { .. } | SchemaNode::Array(_) | SchemaNode::Tuple { .. } => true,
+        SchemaNode::Enum { inner, .. } | SchemaNode::Checked { inner, .. } => needs_bracket(inner),
+        SchemaNode::Ref(slot) => needs_bracket(&ref_target(slot)),
+        _ => false,
+    }
+}
+
+/// 在即将解析的对象里找判别字段的取值，返回对应的备选下标
+fn dispatch(input: &[u8], discriminator: &Discriminator, ctx: &ParseContext) -> Option<usize> {
+    let tag = peek_string_field(input, &discriminator.property)?;
+    let tag = escape::unescape(tag);
+    if let Some(&idx) = discriminator.mapping.get(tag.as_ref()) {
+        return Some(idx);
+    }
+    if ctx.fuzzy_enums {
+        let wanted = normalize_label(&tag);
+        return discriminator
+            .mapping
+            .iter()
+            .find(|(label, _)| normalize_label(label) == wanted)
+            .map(|(_, &idx)| idx);
+    }
+    None
+}
+
+/// 在 `input` 开头的对象这一层里，找 `"property": "value"` 并返回 value 的原始字节
+fn peek_string_field<'t>(input: &'t [u8], property: &[u8]) -> Option<&'t [u8]> {
+    let inside = input.strip_prefix(b"{")?;
+    let scope = &inside[..scope_end(inside).unwrap_or(inside.len())];
+    for quote in [b'"', b'\''] {
+        let mut pattern = Vec::with_capacity(property.len() + 2);
+        pattern.push(quote);
+        pattern.extend_from_slice(property);
+        pattern.push(quote);
+
+        let mut nesting = Nesting::default();
+        let mut scanned = 0;
+        for at in memchr::memmem::find_iter(scope, &pattern) {
+            nesting.feed(&scope[scanned..at]);
+            scanned = at;
+            if !nesting.at_top() {
+                continue;
+            }
+            let rest = scope[at + pattern.len()..].trim_ascii_start();
+            let Some(rest) = rest.strip_prefix(b":") else {
+                continue;
+            };
+            let rest = rest.trim_ascii_start();
+            let Some((&open, body)) = rest.split_first() else {
+                continue;
+            };
+            if open != b'"' && open != b'\'' {
+                continue;
+            }
+            if let Some(end) = memchr::memchr(open, body) {
+                return Some(&body[..end]);
+            }
+        }
+    }
+    None
+}
+
+/// 已经进入某个对象/数组之后，找让这一层闭合的 '}' / ']' 的位置；截断的输入返回 None
+fn scope_end(inside: &[u8]) -> Option<usize> {
+    let mut nesting = Nesting::default();
+    inside.iter().position(|&b| nesting.step(b))
+}
+
+/// 逐字节跟踪括号深度与字符串，用来判断某个位置是否还在起始的那一层
+#[derive(Default)]
+struct Nesting {
+    depth: usize,
+    quote: Option<u8>,
+    escaped: bool,
+    /// 上一个非空白字节（字符串外）
+    prev: u8,
+}
+
+impl Nesting {
+    /// 吃进一个字节；遇到让起始层闭合的 '}' / ']' 时返回 true
+    fn step(&mut self, b: u8) -> bool {
+        if let Some(quote) = self.quote {
+            if self.escaped {
+                self.escaped = false;
+            } else if b == b'\\' {
+                self.escaped = true;
+            } else if b == quote {
+                self.quote = None;
+                self.prev = b;
+            }
+            return false;
+        }
+        match b {
+            b'"' => self.quote = Some(b),
+            // 单引号只在记号开头才算字符串，正文里的撇号（it's）不打乱状态
+            b'\'' if matches!(self.prev, 0 | b'{' | b'[' | b',' | b':') => self.quote = Some(b),
+            b'{' | b'[' => self.depth += 1,
+            b'}' | b']' => {
+                if self.depth == 0 {
+                    return true;
+                }
+                self.depth -= 1;
+            }
+            _ => {}
+        }
+        if !b.is_ascii_whitespace() {
+            self.prev = b;
+        }
+        false
+    }
+
+    fn feed(&mut self, bytes: &[u8]) -> bool {
+        bytes.iter().any(|&b| self.step(b))
+    }
+
+    fn at_top(&self) -> bool {
+        self.depth == 0 && self.quote.is_none()
+    }
+}
+
+/// 按 inner 解析后校验取值；开启宽松匹配时把近似的字符串归一成规范成员
+fn parse_enum<'py>(
+    cursor: &mut Cursor,
+    inner: &SchemaNode,
+    members: &[JsonValue<'static>],
+    py: Python<'py>,
+    ctx: &mut ParseContext,
+) -> Result<PyObject, ParseError> {
+    let start = cursor.pos;
+    let value = parse_value(cursor, inner, py, ctx)?;
+    let value = value.as_ref(py);
+    let to_py = |member: &JsonValue| {
+        member
+            .to_py(py)
+            .map_err(|_| ParseError::InvalidUtf8 { offset: start })
+    };
+
+    for member in members {
+        let candidate = to_py(member)?;
+        if same_value(value, candidate.as_ref(py)) {
+            return Ok(value.into());
+        }
     }
+
+    if ctx.fuzzy_enums {
+        if let Ok(text) = value.extract::<&str>() {
+            let wanted = normalize_label(text);
+            let hit = members.iter().find(|member| {
+                member
+                    .as_str()
+                    .is_some_and(|label| normalize_label(label) == wanted)
+            });
+            if let Some(member) = hit {
+                return to_py(member);
+            }
+        }
+    }
+
+    Err(ParseError::NotInEnum {
+        found: value
+            .repr()
+            .map_or_else(|_| "?".to_string(), |r| r.to_string()),
+        allowed: members.iter().map(JsonValue::to_json).collect(),
+        offset: start,
+    })
+}
+
+/// Python 相等，但 True 不等于 1
+fn same_value(a: &PyAny, b: &PyAny) -> bool {
+    a.is_instance_of::<PyBool>() == b.is_instance_of::<PyBool>() && a.eq(b).unwrap_or(false)
+}
+
+/// 宽松比较用的规范形式：全角转半角、去掉首尾空白、忽略大小写
+fn normalize_label(s: &str) -> String {
+    let halfwidth: String = s
+        .chars()
+        .map(|c| match c {
+            '\u{3000}' => ' ',
+            '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+            _ => c,
+        })
+        .collect();
+    halfwidth.trim().to_lowercase()
 }
 
 fn parse_object<'py>(
@@ -71,42 +769,89 @@ fn parse_object<'py>(
     fields: &FieldLookup,
     required: &ahash::AHashSet<Vec<u8>>,
     ac: &aho_corasick::AhoCorasick,
+    additional: Option<&SchemaNode>,
     py: Python<'py>,
-    depth: usize,
+    ctx: &mut ParseContext,
 ) -> Result<PyObject, ParseError> {
     let dict = PyDict::new(py);
     let mut found_keys = ahash::AHashSet::new(); // 记录找到的 keys
 
-    // 容错：如果没找到 '{'，我们假设已经在里面了（上下文推断），
-    // 但标准情况是必须有 '{'
+    // 容错：根节点漏写 '{' 时把输入当作对象体；嵌套的值没有 '{' 就不是对象
+    // （null、标量），不能把外层对象余下的键当成自己的
     if cursor.matches(b"{") {
         cursor.advance(1);
+    } else if ctx.depth > 0 {
+        return Err(ParseError::TypeMismatch {
+            expected: &["'{'"],
+            offset: cursor.pos,
+        });
     }
+    // 对象的范围：键只在匹配的 '}' 之前、且不在嵌套容器或字符串里时才算数
+    let scope_end = scope_end(cursor.remaining()).map(|end| cursor.pos + end);
 
     loop {
         cursor.skip_whitespace();
 
         if cursor.matches(b"}") || cursor.remaining().is_empty() {
-            cursor.advance(1);
             break;
         }
 
-        // === 核心推测逻辑 (Aho-Corasick) ===
-        // 使用 AC 自动机在剩余文本中搜索所有可能的 Key
         let input = cursor.remaining();
         let mut found_match = false;
 
-        // 限制搜索范围，避免在超大文本中搜索太久
-        // 假设 Key 不会离当前位置太远（例如 1KB 内）
-        // 注意：AC 搜索是流式的，但为了简单起见，我们先在 slice 上搜
-        // 实际上 AC 很快，搜整个 remaining 通常也没问题，除非 remaining 巨大
-        // 为了安全起见，我们可以限制搜索窗口，但如果 JSON 结构非常松散，限制窗口可能导致找不到
-        // 鉴于 AC 的高性能，我们先尝试搜索整个 remaining (或者一个较大的窗口)
+        // 限制搜索范围：对象闭合时搜到它的 '}' 为止，截断时只向前看一个窗口
+        let limit = match scope_end {
+            Some(end) => end.saturating_sub(cursor.pos).min(input.len()),
+            None => input.len().min(KEY_SEARCH_WINDOW),
+        };
+        let input = &input[..limit];
+
+        // additionalProperties：这一层的每个带引号的键都要，中间隔着噪声也一样；
+        // 已知键按自己的 Schema，其余按 additional
+        if let Some(extra) = additional {
+            let Some((key, value_at)) = next_quoted_key(input) else {
+                break;
+            };
+            let sub_schema = fields.get(key);
+            let key_str = escape::unescape(key).into_owned();
+            cursor.advance(value_at);
+            ctx.enter(|| key_str.clone());
+            let in_array = std::mem::replace(&mut ctx.in_array, false);
+            let schema = sub_schema.map_or(extra, |schema| schema.as_ref());
+            cursor.skip_whitespace();
+            let value_at = cursor.pos;
+            let val = parse_node_with(cursor, schema, py, ctx);
+            ctx.in_array = in_array;
+            ctx.leave();
+            let val = val
+                .or_else(|e| optional_null(cursor, e, value_at, required.contains(key), py))
+                .map_err(|e| e.within(|| key_str.clone()))?;
+            dict.set_item(key_str, val)
+                .map_err(|_| ParseError::InvalidUtf8 { offset: cursor.pos })?;
+            if sub_schema.is_some() {
+                found_keys.insert(key.to_vec());
+            }
+            skip_separator(cursor);
+            continue;
+        }
+
+        // === 核心推测逻辑 (Aho-Corasick) ===
+        // 使用 AC 自动机在剩余文本中搜索所有可能的 Key
+        let mut nesting = Nesting::default();
+        let mut scanned = 0;
 
         // 迭代查找所有匹配项
         // println!("DEBUG: Searching in input: {:?}", String::from_utf8_lossy(input));
         for mat in ac.find_iter(input) {
             let _pattern_id = mat.pattern();
+            // 嵌套对象里的同名键不属于这一层；遇到让这一层闭合的括号就停止
+            if nesting.feed(&input[scanned..mat.start()]) {
+                break;
+            }
+            scanned = mat.start();
+            if !nesting.at_top() {
+                continue;
+            }
             let end = mat.end();
             // println!("DEBUG: Found match at {:?}-{:?}", mat.start(), mat.end());
 
@@ -138,12 +883,23 @@ fn parse_object<'py>(
 
                 // 3. 解析 Value
                 if let Some(sub_schema) = fields.get(key_content) {
-                    let val = parse_node(cursor, sub_schema, py, depth + 1)?;
-
                     // 安全的 UTF-8 转换
                     let key_str = String::from_utf8_lossy(key_content);
+
+                    ctx.enter(|| key_str.to_string());
+                    let in_array = std::mem::replace(&mut ctx.in_array, false);
+                    cursor.skip_whitespace();
+                    let value_at = cursor.pos;
+                    let val = parse_node_with(cursor, sub_schema, py, ctx);
+                    ctx.in_array = in_array;
+                    ctx.leave();
+                    let is_required = required.contains(key_content);
+                    let val = val
+                        .or_else(|e| optional_null(cursor, e, value_at, is_required, py))
+                        .map_err(|e| e.within(|| key_str.to_string()))?;
+
                     dict.set_item(key_str, val)
-                        .map_err(|_| ParseError::InvalidUtf8)?;
+                        .map_err(|_| ParseError::InvalidUtf8 { offset: cursor.pos })?;
                     found_keys.insert(key_content.to_vec());
 
                     found_match = true;
@@ -157,34 +913,117 @@ fn parse_object<'py>(
             break;
         }
 
-        cursor.skip_whitespace();
-        if cursor.matches(b",") {
-            cursor.advance(1);
+        skip_separator(cursor);
+    }
+
+    // 键之后只剩噪声时，直接跳到这一层的 '}'
+    if let Some(end) = scope_end {
+        if cursor.pos < end {
+            cursor.pos = end;
         }
     }
 
+    // 缺失字段报在对象结束处（它本该出现在 '}' 之前）
+    let object_end = cursor.pos;
+    if cursor.matches(b"}") || cursor.remaining().is_empty() {
+        cursor.advance(1);
+    }
+
     // === 审计阶段 ===
     for req in required {
         if !found_keys.contains(req) {
-            return Err(ParseError::MissingField(
-                String::from_utf8_lossy(req).to_string(),
-            ));
+            return Err(ParseError::MissingField {
+                field: String::from_utf8_lossy(req).to_string(),
+                offset: object_end,
+            });
         }
     }
 
     Ok(dict.into())
 }
 
+/// 可选字段写成裸 null、而 Schema 不接受 null 时，按没填处理：取 None 并跳过 null，
+/// 不拖垮整个对象。只认值本身就是 null 的情况，值内部更深处的类型错误照常上报
+fn optional_null(
+    cursor: &mut Cursor,
+    err: ParseError,
+    value_at: usize,
+    required: bool,
+    py: Python<'_>,
+) -> Result<PyObject, ParseError> {
+    if let ParseError::TypeMismatch { offset, .. } = err {
+        let mut value = *cursor;
+        value.pos = value_at;
+        if !required && offset == value_at {
+            if let Some(len) = null_literal_len(value.remaining()) {
+                cursor.pos = value_at + len;
+                return Ok(py.None());
+            }
+        }
+    }
+    Err(err)
+}
+
+/// 跳过值后面的空白和一个逗号
+fn skip_separator(cursor: &mut Cursor) {
+    cursor.skip_whitespace();
+    if cursor.matches(b",") {
+        cursor.advance(1);
+    }
+}
+
+/// 对象这一层里下一个带引号的键：返回键的原始字节，以及冒号之后的偏移；
+/// 嵌套容器和字符串值里的不算，遇到这一层的 '}' 就停止
+fn next_quoted_key(input: &[u8]) -> Option<(&[u8], usize)> {
+    let mut nesting = Nesting::default();
+    for (i, &b) in input.iter().enumerate() {
+        // 与 Nesting 一致：单引号只在记号开头才可能是键
+        let opens = b == b'"' || (b == b'\'' && matches!(nesting.prev, 0 | b'{' | b','));
+        if opens && nesting.at_top() {
+            if let Some((key, value_at)) = quoted_key(&input[i..]) {
+                return Some((key, i + value_at));
+            }
+        }
+        if nesting.step(b) {
+            return None;
+        }
+    }
+    None
+}
+
+/// `input` 开头的带引号键（后面须跟 ':'）：返回键的原始字节，以及冒号之后的偏移
+fn quoted_key(input: &[u8]) -> Option<(&[u8], usize)> {
+    let (&quote, body) = input.split_first()?;
+    if quote != b'"' && quote != b'\'' {
+        return None;
+    }
+    let mut len = 0;
+    while len < body.len() && body[len] != quote {
+        len += if body[len] == b'\\' { 2 } else { 1 };
+    }
+    let after = body.get(len + 1..)?;
+    let ws = after.iter().take_while(|b| b.is_ascii_whitespace()).count();
+    (after.get(ws) == Some(&b':')).then_some((&body[..len], len + ws + 3))
+}
+
+/// 数组与元组：下标落在 prefix 内时按对应位置的 Schema 解析，其余按 items
 fn parse_array<'py>(
     cursor: &mut Cursor,
-    inner: &SchemaNode,
+    prefix: &[Arc<SchemaNode>],
+    items: &SchemaNode,
     py: Python<'py>,
-    depth: usize,
+    ctx: &mut ParseContext,
 ) -> Result<PyObject, ParseError> {
     let list = PyList::empty(py);
 
+    // 与对象相同：只有根节点容忍漏写的 '['，嵌套的值不是数组时不能吞掉后面的兄弟字段
     if cursor.matches(b"[") {
         cursor.advance(1);
+    } else if ctx.depth > 0 {
+        return Err(ParseError::TypeMismatch {
+            expected: &["'['"],
+            offset: cursor.pos,
+        });
     }
 
     loop {
@@ -195,8 +1034,16 @@ fn parse_array<'py>(
         }
 
         let start_pos = cursor.pos;
-        let val = parse_node(cursor, inner, py, depth + 1)?;
-        list.append(val).map_err(|_| ParseError::InvalidUtf8)?;
+        let index = list.len();
+        let inner = prefix.get(index).map_or(items, |schema| schema.as_ref());
+        ctx.enter(|| index.to_string());
+        let in_array = std::mem::replace(&mut ctx.in_array, true);
+        let val = parse_node_with(cursor, inner, py, ctx);
+        ctx.in_array = in_array;
+        ctx.leave();
+        let val = val.map_err(|e| e.within(|| index.to_string()))?;
+        list.append(val)
+            .map_err(|_| ParseError::InvalidUtf8 { offset: cursor.pos })?;
 
         if cursor.pos == start_pos {
             // Stuck! Force advance to avoid infinite loop
@@ -216,23 +1063,16 @@ fn parse_array<'py>(
     Ok(list.into())
 }
 
-/// 鲁棒的数字解析
-fn parse_number_robust<'py>(cursor: &mut Cursor, py: Python<'py>) -> Result<PyObject, ParseError> {
-    let _start = cursor.pos;
+/// 鲁棒的数字解析；`grouping` 为 false 时（数组元素）逗号不当千分位
+fn parse_number_robust<'py>(
+    cursor: &mut Cursor,
+    py: Python<'py>,
+    grouping: bool,
+) -> Result<PyObject, ParseError> {
+    let start = cursor.pos;
     let input = cursor.remaining();
-    let mut end = 0;
-
-    // 贪婪匹配所有可能组成数字的字符
-    // 容忍 '1,000' 中的逗号
-    while end < input.len() {
-        match input[end] {
-            b'0'..=b'9' | b'.' | b'-' | b'+' | b'e' | b'E' | b',' => end += 1,
-            _ => break,
-        }
-    }
-
-    cursor.advance(end);
-    let raw_bytes = &input[..end];
+    let (skip, len, end) = number_token(input, grouping);
+    let raw_bytes = &input[skip..skip + len];
 
     // 优化：先检查是否存在逗号。memchr 极快。
     let has_comma = memchr::memchr(b',', raw_bytes).is_some();
@@ -241,20 +1081,152 @@ fn parse_number_robust<'py>(cursor: &mut Cursor, py: Python<'py>) -> Result<PyOb
         // 快乐路径：完全零拷贝
         // 安全性：我们在上面的循环里只允许了 [0-9.-+eE]
         let s = unsafe { std::str::from_utf8_unchecked(raw_bytes) };
-        s.parse::<f64>().unwrap_or(0.0)
+        s.parse::<f64>()
     } else {
         // 悲伤路径：只有遇到逗号才分配内存
         let s = String::from_utf8_lossy(raw_bytes);
-        s.replace(',', "").parse::<f64>().unwrap_or(0.0)
+        s.replace(',', "").parse::<f64>()
     };
 
+    // 不是数字就报错，不再悄悄变成 0.0（否则 minimum 之类的约束也无从谈起）
+    let Ok(float_val) = float_val else {
+        return Err(ParseError::TypeMismatch {
+            expected: &["a number"],
+            offset: start,
+        });
+    };
+    cursor.advance(end);
     Ok(PyFloat::new(py, float_val).into())
 }
 
-/// 推测性字符串解析
+/// 整数解析：接受 `1,000`、`+5`、`"12"`，以及数值为整数的 `1e3` / `2.50e1`；
+/// 有非零小数部分时报错而不是悄悄截断
+fn parse_integer_robust<'py>(
+    cursor: &mut Cursor,
+    py: Python<'py>,
+    grouping: bool,
+) -> Result<PyObject, ParseError> {
+    let start = cursor.pos;
+    let input = cursor.remaining();
+    let (skip, len, end) = number_token(input, grouping);
+    let raw = &input[skip..skip + len];
+
+    if raw.is_empty() {
+        return Err(ParseError::TypeMismatch {
+            expected: &["an integer"],
+            offset: start,
+        });
+    }
+    let Some(digits) = integral_digits(raw) else {
+        return Err(ParseError::InvalidInteger {
+            literal: String::from_utf8_lossy(raw).into_owned(),
+            offset: start,
+        });
+    };
+    cursor.advance(end);
+
+    if let Ok(i) = digits.parse::<i64>() {
+        return Ok(i.into_py(py));
+    }
+    // 超出 i64：交给 Python int() 做任意精度
+    let invalid = |_| ParseError::InvalidInteger {
+        literal: digits.clone(),
+        offset: start,
+    };
+    let builtins = py.import("builtins").map_err(invalid)?;
+    let int = builtins.getattr("int").map_err(invalid)?;
+    Ok(int.call1((digits.as_str(),)).map_err(invalid)?.into())
+}
+
+/// 数字记号在 `input` 里的位置：(起点, 长度, 连同引号一共消耗的字节数)。
+/// 与布尔一样接受带引号的写法 `"12"`，引号里的逗号总是千分位
+fn number_token(input: &[u8], grouping: bool) -> (usize, usize, usize) {
+    if let Some((&quote @ (b'"' | b'\''), body)) = input.split_first() {
+        let len = number_token_len(body, true);
+        if len > 0 && body.get(len) == Some(&quote) {
+            return (1, len, len + 2);
+        }
+    }
+    let len = number_token_len(input, grouping);
+    (0, len, len)
+}
+
+/// 数字记号的长度：贪婪匹配 [0-9.+-eE]，`grouping` 时容忍 `1,000` 中的千分位逗号。
+/// 逗号后面恰好是三位数字才算千分位；数组元素里不做这种推测，`[1,250]` 是两个数
+fn number_token_len(input: &[u8], grouping: bool) -> usize {
+    let mut end = 0;
+    while end < input.len() {
+        match input[end] {
+            b'0'..=b'9' | b'.' | b'-' | b'+' | b'e' | b'E' => end += 1,
+            b',' if grouping && is_thousands_group(&input[end + 1..]) => end += 1,
+            _ => break,
+        }
+    }
+    end
+}
+
+fn is_thousands_group(rest: &[u8]) -> bool {
+    rest.len() >= 3
+        && rest[..3].iter().all(u8::is_ascii_digit)
+        && !rest.get(3).is_some_and(u8::is_ascii_digit)
+}
+
+/// 把数字记号规范成十进制整数字符串（可带 '-'）；有非零小数部分或不是数字时返回 None
+fn integral_digits(raw: &[u8]) -> Option<String> {
+    let s: String = raw
+        .iter()
+        .filter(|&&b| b != b',')
+        .map(|&b| b as char)
+        .collect();
+    let (negative, unsigned) = match s.as_bytes().first() {
+        Some(b'-') => (true, &s[1..]),
+        Some(b'+') => (false, &s[1..]),
+        _ => (false, s.as_str()),
+    };
+    let (mantissa, exp) = match unsigned.find(['e', 'E']) {
+        Some(i) => (&unsigned[..i], unsigned[i + 1..].parse::<i64>().ok()?),
+        None => (unsigned, 0),
+    };
+    let (int_part, frac_part) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let all_digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+    if (int_part.is_empty() && frac_part.is_empty())
+        || !all_digits(int_part)
+        || !all_digits(frac_part)
+    {
+        return None;
+    }
+
+    // 数值 = 全部数字 × 10^scale
+    let mut digits = format!("{int_part}{frac_part}");
+    let scale = exp.checked_sub(frac_part.len() as i64)?;
+    if scale >= 0 {
+        if digits.len() as i64 + scale > MAX_INTEGER_DIGITS as i64 {
+            return None;
+        }
+        digits.extend(std::iter::repeat_n('0', scale as usize));
+    } else {
+        // 被移到小数点后的数字必须全是 0
+        let keep = digits.len().saturating_sub(scale.unsigned_abs() as usize);
+        if digits[keep..].bytes().any(|b| b != b'0') {
+            return None;
+        }
+        digits.truncate(keep);
+    }
+
+    let magnitude = digits.trim_start_matches('0');
+    Some(match magnitude {
+        "" => "0".to_string(),
+        m if negative => format!("-{m}"),
+        m => m.to_string(),
+    })
+}
+
+/// 推测性字符串解析。字段里的引号要后面跟着结构符号才算结束（容忍未转义的引号）；
+/// `first_quote` 时（根节点，两边都是正文）第一个未转义的同类引号就是结束
 fn parse_string_speculative<'py>(
     cursor: &mut Cursor,
     py: Python<'py>,
+    first_quote: bool,
 ) -> Result<PyObject, ParseError> {
     let start_quote = if cursor.matches(b"\"") {
         Some(b'"')
@@ -275,13 +1247,14 @@ fn parse_string_speculative<'py>(
 
         // Quoted string mode: STRICT
         let input = cursor.remaining();
+        let closes = |rest: &[u8]| first_quote || is_structural_closure(rest);
         let mut len = 0;
         let mut escape = false;
 
         while len < input.len() {
             if len > MAX_STRING_LEN {
                 // String too long
-                return Ok(PyString::new(py, &String::from_utf8_lossy(&input[..len])).into());
+                return Ok(PyString::new(py, &escape::unescape(&input[..len])).into());
             }
 
             let b = input[len];
@@ -297,18 +1270,18 @@ fn parse_string_speculative<'py>(
                 // 2. Whitespace + Comma + (Key or End)
 
                 let rest = &input[len + 1..];
-                if is_structural_closure(rest) {
+                if closes(rest) {
                     cursor.advance(len + 1);
-                    let s = String::from_utf8_lossy(&input[..len]);
+                    let s = escape::unescape(&input[..len]);
                     return Ok(PyString::new(py, &s).into());
                 }
                 // Else: Treat as content
             } else if quote_type == b'\x82' && b == b'"' {
                 // Allow standard quote to close fullwidth quote if followed by closure
                 let rest = &input[len + 1..];
-                if is_structural_closure(rest) {
+                if closes(rest) {
                     cursor.advance(len + 1);
-                    let s = String::from_utf8_lossy(&input[..len]);
+                    let s = escape::unescape(&input[..len]);
                     return Ok(PyString::new(py, &s).into());
                 }
             } else if quote_type == b'\x82'
@@ -319,9 +1292,9 @@ fn parse_string_speculative<'py>(
             {
                 // Found potential fullwidth closing quote
                 let rest = &input[len + 3..];
-                if is_structural_closure(rest) {
+                if closes(rest) {
                     cursor.advance(len + 3);
-                    let s = String::from_utf8_lossy(&input[..len]);
+                    let s = escape::unescape(&input[..len]);
                     return Ok(PyString::new(py, &s).into());
                 }
             }
@@ -329,7 +1302,9 @@ fn parse_string_speculative<'py>(
         }
 
         // Hit EOF without closing quote -> Error
-        return Err(ParseError::UnexpectedEof);
+        Err(ParseError::UnexpectedEof {
+            offset: cursor.pos + input.len(),
+        })
     } else {
         // Unquoted string mode: ROBUST / HEURISTIC
         // Consume until a separator is found
@@ -358,12 +1333,6 @@ fn parse_string_speculative<'py>(
 
         cursor.advance(len);
         let s = String::from_utf8_lossy(&input[..len]);
-
-        // Special handling for null -> None
-        if s == "null" {
-            return Ok(py.None().into());
-        }
-
         Ok(PyString::new(py, &s).into())
     }
 }
@@ -372,24 +1341,43 @@ fn parse_bool_speculative<'py>(
     cursor: &mut Cursor,
     py: Python<'py>,
 ) -> Result<PyObject, ParseError> {
-    if cursor.matches(b"true") {
-        cursor.advance(4);
-        Ok(PyBool::new(py, true).into())
-    } else if cursor.matches(b"false") {
-        cursor.advance(5);
-        Ok(PyBool::new(py, false).into())
-    } else {
-        // 也许是 "True" 或 "False" (Python 风格)
-        if cursor.matches(b"True") {
-            cursor.advance(4);
-            Ok(PyBool::new(py, true).into())
-        } else if cursor.matches(b"False") {
-            cursor.advance(5);
-            Ok(PyBool::new(py, false).into())
-        } else {
-            Ok(py.None().into())
+    // true / false，也接受 Python 风格的 True / False 和带引号的 "true"
+    match bool_literal(cursor.remaining()) {
+        Some((value, len)) => {
+            cursor.advance(len);
+            Ok(PyBool::new(py, value).into())
         }
+        // 不认识的写法直接报错，不再悄悄变成 None
+        None => Err(ParseError::TypeMismatch {
+            expected: &["true", "false"],
+            offset: cursor.pos,
+        }),
+    }
+}
+
+/// 逗号后面是否像元组里的下一个非字符串元素：`[`、`{`，或后面紧跟 `,` `]` `}` 的数字/字面量。
+/// `"cat", 0.9]` 里的引号因此算作结束，而 `"hi", 3 times"` 仍当作字符串内容
+fn starts_bare_element(input: &[u8]) -> bool {
+    if matches!(input.first(), Some(b'[' | b'{')) {
+        return true;
     }
+    let len = input
+        .iter()
+        .take_while(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'+' | b'.'))
+        .count();
+    let token = &input[..len];
+    let bare = matches!(token, b"true" | b"false" | b"null")
+        || (token
+            .first()
+            .is_some_and(|b| b.is_ascii_digit() || *b == b'-')
+            && token
+                .iter()
+                .skip(1)
+                .all(|b| b.is_ascii_digit() || matches!(b, b'-' | b'+' | b'.' | b'e' | b'E')));
+    bare && matches!(
+        input[len..].iter().find(|b| !b.is_ascii_whitespace()),
+        None | Some(b',' | b']' | b'}')
+    )
 }
 
 fn is_structural_closure(input: &[u8]) -> bool {
@@ -423,7 +1411,7 @@ fn is_structural_closure(input: &[u8]) -> bool {
             return true; // Trailing comma at EOF
         }
         let next_b = after_comma[next_idx];
-        if next_b == b'"' || next_b == b'}' {
+        if next_b == b'"' || next_b == b'}' || starts_bare_element(&after_comma[next_idx..]) {
             return true;
         }
         // Fullwidth quote or brace
@@ -449,7 +1437,7 @@ fn is_structural_closure(input: &[u8]) -> bool {
             return true;
         }
         let next_b = after_comma[next_idx];
-        if next_b == b'"' || next_b == b'}' {
+        if next_b == b'"' || next_b == b'}' || starts_bare_element(&after_comma[next_idx..]) {
             return true;
         }
         if next_b == 0xEF && next_idx + 2 < after_comma.len() && after_comma[next_idx + 1] == 0xBC {
@@ -1,10 +1,14 @@
+use super::constraints::Check;
+use crate::repair::JsonValue;
 use ahash::{AHashMap, AHashSet};
 use smallvec::SmallVec;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock, Weak};
 
 /// 阈值：字段数少于 16 时，线性扫描通常比 Hash 计算快，且省内存
 pub const SMALL_MAP_THRESHOLD: usize = 16;
 
+// Small 变体刻意内联存储，避免多一次指针跳转
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone)]
 pub enum FieldLookup {
     /// 极速路径：CPU 缓存友好的线性存储
@@ -28,19 +32,69 @@ impl FieldLookup {
             FieldLookup::Large(map) => map.get(key),
         }
     }
+
+    pub fn keys(&self) -> Vec<&[u8]> {
+        match self {
+            FieldLookup::Small(vec) => vec.iter().map(|(k, _)| k.as_slice()).collect(),
+            FieldLookup::Large(map) => map.keys().map(Vec::as_slice).collect(),
+        }
+    }
+}
+
+/// 递归 `$ref` 的回填槽
+pub type RefSlot = Arc<OnceLock<Weak<SchemaNode>>>;
+
+/// `anyOf` / `oneOf` 的判别字段：属性名，以及它的取值对应哪个备选
+#[derive(Debug, Clone)]
+pub struct Discriminator {
+    pub property: Vec<u8>,
+    pub mapping: AHashMap<String, usize>,
 }
 
+// 节点总是通过 Arc 共享，变体大小差异无所谓
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone)]
 pub enum SchemaNode {
     PrimitiveString,
     PrimitiveNumber,
+    /// 整数：返回任意精度的 Python int，带非零小数部分时报错
+    PrimitiveInteger,
     PrimitiveBool,
+    /// 只接受裸字面量 null / None
+    PrimitiveNull,
+    /// `"type": ["X", "null"]`：先认 null，否则按 X 解析
+    Nullable(Arc<SchemaNode>),
     Array(Arc<SchemaNode>),
+    /// `prefixItems`：前几个位置各有 Schema，其余元素按 items 解析
+    Tuple {
+        prefix: Vec<Arc<SchemaNode>>,
+        items: Arc<SchemaNode>,
+    },
+    /// `anyOf` / `oneOf`：有判别字段时直接分派，否则逐个尝试，取锚定字段最多的结果
+    Union {
+        alternatives: Vec<Arc<SchemaNode>>,
+        discriminator: Option<Discriminator>,
+    },
+    /// 递归 `$ref` 的回指：目标编译完成后回填。用 Weak 避免循环引用泄漏，
+    /// 目标节点总是由外层的引用方持有
+    Ref(RefSlot),
+    /// 长度、pattern、format 等约束：先按 inner 解析，再逐条校验
+    Checked {
+        inner: Arc<SchemaNode>,
+        checks: Vec<Check>,
+    },
+    /// `enum` / `const`：先按 inner 解析，再要求结果是 members 之一
+    Enum {
+        inner: Arc<SchemaNode>,
+        members: Vec<JsonValue<'static>>,
+    },
     Object {
         fields: FieldLookup,
         required: AHashSet<Vec<u8>>,
         /// Aho-Corasick 自动机，用于快速查找 Key
         ac: Arc<aho_corasick::AhoCorasick>,
+        /// `additionalProperties` 为 true 或子 Schema 时，未知的带引号键按它解析并保留
+        additional: Option<Arc<SchemaNode>>,
     },
     Any, // 对应 Schema 中的 {}，放弃 Schema 驱动，退化为通用解析
 }
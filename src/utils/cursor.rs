@@ -1,3 +1,4 @@
+#[derive(Debug, Clone, Copy)]
 pub struct Cursor<'a> {
     input: &'a [u8],
     pub pos: usize,
@@ -9,6 +10,12 @@ impl<'a> Cursor<'a> {
         Self { input, pos: 0 }
     }
 
+    /// 从 `pos` 开始解析，但保留完整输入，错误偏移因此是全文的绝对位置
+    #[inline(always)]
+    pub fn at(input: &'a [u8], pos: usize) -> Self {
+        Self { input, pos }
+    }
+
     #[inline(always)]
     pub fn remaining(&self) -> &'a [u8] {
         if self.pos >= self.input.len() {
@@ -23,6 +30,12 @@ impl<'a> Cursor<'a> {
         self.pos += n;
     }
 
+    /// 当前位置，截断到输入长度（容器在 EOF 处自动闭合时 pos 会越过末尾）
+    #[inline(always)]
+    pub fn offset(&self) -> usize {
+        self.pos.min(self.input.len())
+    }
+
     /// 极速跳过空白字符
     pub fn skip_whitespace(&mut self) {
         while self.pos < self.input.len() {
@@ -0,0 +1,92 @@
+use std::borrow::Cow;
+
+/// 解码反斜杠之后的转义序列（`input` 从反斜杠的下一个字节开始），结果追加到 `out`，
+/// 返回消耗的字节数；反斜杠后面已是输入末尾时返回 None。
+///
+/// 与 repair 的策略一致：未知转义与坏掉的 `\u` 原样保留；`\uD83D\uDE00` 这样的代理对
+/// 合并成一个字符，落单的代理项同样原样保留。
+pub fn decode_escape(input: &[u8], out: &mut String) -> Option<usize> {
+    let (&esc, rest) = input.split_first()?;
+    match esc {
+        b'n' => out.push('\n'),
+        b'r' => out.push('\r'),
+        b't' => out.push('\t'),
+        b'b' => out.push('\x08'),
+        b'f' => out.push('\x0c'),
+        b'"' | b'\'' | b'\\' | b'/' => out.push(esc as char),
+        b'u' => return Some(1 + decode_unicode(rest, out)),
+        _ => {
+            // 未知转义：只保留反斜杠，后面的字符按普通内容处理
+            out.push('\\');
+            return Some(0);
+        }
+    }
+    Some(1)
+}
+
+/// `\u` 之后的部分，返回消耗的字节数
+fn decode_unicode(input: &[u8], out: &mut String) -> usize {
+    let Some(high) = hex4(input) else {
+        let digits = input
+            .iter()
+            .take(4)
+            .take_while(|b| b.is_ascii_hexdigit())
+            .count();
+        out.push_str("\\u");
+        out.extend(input[..digits].iter().map(|&b| b as char));
+        return digits;
+    };
+
+    if let Some(c) = char::from_u32(high) {
+        out.push(c);
+        return 4;
+    }
+    // 高代理项后面紧跟低代理项才能组成一个字符
+    if (0xD800..0xDC00).contains(&high) && input[4..].starts_with(b"\\u") {
+        if let Some(low @ 0xDC00..=0xDFFF) = hex4(&input[6..]) {
+            let combined = 0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00);
+            if let Some(c) = char::from_u32(combined) {
+                out.push(c);
+                return 10;
+            }
+        }
+    }
+    out.push_str("\\u");
+    out.extend(input[..4].iter().map(|&b| b as char));
+    4
+}
+
+fn hex4(input: &[u8]) -> Option<u32> {
+    let digits = input.get(..4)?;
+    if !digits.iter().all(u8::is_ascii_hexdigit) {
+        return None;
+    }
+    u32::from_str_radix(std::str::from_utf8(digits).ok()?, 16).ok()
+}
+
+/// 解码字符串内容（不含两侧引号）里的所有转义；非法 UTF-8 按 `from_utf8_lossy` 替换。
+/// 没有反斜杠时不做任何拷贝之外的工作。
+pub fn unescape(raw: &[u8]) -> Cow<'_, str> {
+    let Some(first) = memchr::memchr(b'\\', raw) else {
+        return String::from_utf8_lossy(raw);
+    };
+
+    let mut out = String::with_capacity(raw.len());
+    out.push_str(&String::from_utf8_lossy(&raw[..first]));
+    let mut rest = &raw[first + 1..];
+    loop {
+        // 末尾落单的反斜杠与 repair 一样直接丢弃
+        let used = decode_escape(rest, &mut out).unwrap_or(rest.len());
+        rest = &rest[used..];
+        match memchr::memchr(b'\\', rest) {
+            Some(idx) => {
+                out.push_str(&String::from_utf8_lossy(&rest[..idx]));
+                rest = &rest[idx + 1..];
+            }
+            None => {
+                out.push_str(&String::from_utf8_lossy(rest));
+                return Cow::Owned(out);
+            }
+        }
+    }
+}
@@ -0,0 +1,58 @@
+/// 片段截取时，偏移两侧各保留的字节数
+const SNIPPET_RADIUS: usize = 24;
+
+/// 错误定位：字节偏移、字符偏移、行列号（从 1 开始，列按字符计）以及附近的原文片段
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Location {
+    pub offset: usize,
+    pub char_offset: usize,
+    pub line: usize,
+    pub column: usize,
+    pub snippet: String,
+}
+
+impl Location {
+    /// 只记录偏移，行列号留到 `resolve` 时再算（解析失败很常见，别每次都扫全文）
+    pub(crate) fn unresolved(offset: usize) -> Self {
+        Location {
+            offset,
+            ..Location::default()
+        }
+    }
+
+    pub fn new(input: &[u8], offset: usize) -> Self {
+        let offset = offset.min(input.len());
+        let before = &input[..offset];
+        let line_start = memchr::memrchr(b'\n', before).map_or(0, |i| i + 1);
+
+        Location {
+            offset,
+            char_offset: count_chars(before),
+            line: memchr::memchr_iter(b'\n', before).count() + 1,
+            column: count_chars(&before[line_start..]) + 1,
+            snippet: snippet(input, offset),
+        }
+    }
+
+    pub(crate) fn resolve(&mut self, input: &[u8]) {
+        *self = Location::new(input, self.offset);
+    }
+}
+
+/// UTF-8 字符数：只数非续字节
+fn count_chars(bytes: &[u8]) -> usize {
+    bytes.iter().filter(|&&b| (b & 0xC0) != 0x80).count()
+}
+
+fn snippet(input: &[u8], offset: usize) -> String {
+    let mut start = offset.saturating_sub(SNIPPET_RADIUS);
+    let mut end = (offset + SNIPPET_RADIUS).min(input.len());
+    // 对齐到字符边界，避免切碎多字节字符
+    while start > 0 && (input[start] & 0xC0) == 0x80 {
+        start -= 1;
+    }
+    while end < input.len() && (input[end] & 0xC0) == 0x80 {
+        end += 1;
+    }
+    String::from_utf8_lossy(&input[start..end]).into_owned()
+}
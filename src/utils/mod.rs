@@ -1 +1,3 @@
 pub mod cursor;
+pub mod escape;
+pub mod location;
@@ -0,0 +1,598 @@
+//! 纯 Rust 路径的测试：不依赖 Python 解释器，`--no-default-features` 下同样运行。
+
+use llm_json_utils::repair::{self, JsonValue, Number, RepairErrorKind, RepairKind, RepairOptions};
+use llm_json_utils::structural::constraints::{Check, Format};
+use llm_json_utils::utils::escape;
+use std::borrow::Cow;
+use std::fs;
+use std::path::PathBuf;
+
+fn fixture_files(dir: &str) -> Vec<PathBuf> {
+    let mut entries: Vec<_> = fs::read_dir(dir)
+        .expect("Failed to read fixture directory")
+        .map(|res| res.map(|e| e.path()))
+        .collect::<Result<_, _>>()
+        .expect("Failed to collect paths");
+    entries.retain(|p| p.extension().and_then(|s| s.to_str()) == Some("txt"));
+    entries.sort();
+    entries
+}
+
+#[test]
+fn test_repair_native_value() {
+    // 纯 Rust 路径：不需要 Python 解释器
+    let value = repair::repair_json(
+        "Here: {\"a\": [1, 2.5, \"x\",], \"b\": None, \"n\": 123456789012345678901234567890",
+    )
+    .expect("should repair");
+    let items = value
+        .get("a")
+        .and_then(JsonValue::as_array)
+        .expect("a is array");
+    assert_eq!(items[0], JsonValue::Number(Number::Int(1)));
+    assert_eq!(items[1], JsonValue::Number(Number::Float(2.5)));
+    assert_eq!(items[2].as_str(), Some("x"));
+    assert!(value.get("b").is_some_and(JsonValue::is_null));
+    assert_eq!(
+        value.get("n"),
+        Some(&JsonValue::Number(Number::BigInt(
+            "123456789012345678901234567890".to_string()
+        )))
+    );
+
+    for path in fixture_files("tests/success/repair") {
+        let content = fs::read_to_string(&path).expect("Failed to read file");
+        assert!(
+            repair::repair_json(&content).is_ok(),
+            "Expected success for {:?}",
+            path
+        );
+    }
+
+    for path in fixture_files("tests/failure/repair") {
+        let content = fs::read_to_string(&path).expect("Failed to read file");
+        assert!(
+            repair::repair_json(&content).is_err(),
+            "Expected failure for {:?}",
+            path
+        );
+    }
+}
+
+#[test]
+fn test_repair_strings_borrow_unless_escaped() {
+    let input = "{\"plain\": \"中文 text\", \"esc\": \"a\\nb\\u4e2d\\uZZ\\q\", \"open\": \"tail";
+    let value = repair::repair_json(input).expect("should repair");
+
+    assert!(matches!(
+        value.get("plain"),
+        Some(JsonValue::String(Cow::Borrowed("中文 text")))
+    ));
+    // 坏掉的 \u 与未知转义原样保留
+    assert_eq!(
+        value.get("esc").and_then(JsonValue::as_str),
+        Some("a\nb中\\uZZ\\q")
+    );
+    assert_eq!(value.get("open").and_then(JsonValue::as_str), Some("tail"));
+
+    let owned: JsonValue<'static> = value.clone().into_owned();
+    assert_eq!(owned, value);
+}
+
+#[test]
+fn test_escape_surrogate_pairs_and_unknown_escapes() {
+    let value =
+        repair::repair_json(r#"["\ud83d\ude00", "\ud83d x", "\ude00", "\é\u00e9"]"#).unwrap();
+    let got: Vec<&str> = value
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(JsonValue::as_str)
+        .collect();
+    assert_eq!(got, ["😀", "\\ud83d x", "\\ude00", "\\éé"]);
+
+    // 结构化提取用的是同一套规则
+    assert_eq!(
+        escape::unescape(br#"a\nb\ud83d\ude00\q\u12"#),
+        "a\nb😀\\q\\u12"
+    );
+    assert!(matches!(escape::unescape(b"plain"), Cow::Borrowed("plain")));
+    assert_eq!(escape::unescape(b"tail\\"), "tail");
+}
+
+#[test]
+fn test_string_formats() {
+    let cases = [
+        (
+            Format::Date,
+            vec!["2024-02-29", "1999-12-31"],
+            vec!["2023-02-29", "2024-13-01", "2024-1-01"],
+        ),
+        (
+            Format::DateTime,
+            vec![
+                "2024-05-01T12:30:00Z",
+                "2024-05-01t23:59:60.123+08:00",
+                "2024-05-01 00:00:00-05:30",
+            ],
+            vec![
+                "2024-05-01T12:30:00",
+                "2024-05-01T24:00:00Z",
+                "2024-05-01T12:30Z",
+            ],
+        ),
+        (
+            Format::Email,
+            vec!["a.b@example.co", "用户@例子.中国"],
+            vec!["a@b", "a b@c.d", "@c.d", "a@@c.d", "a@c..d"],
+        ),
+        (
+            Format::Uri,
+            vec!["https://x.org/a?b", "urn:isbn:0451450523", "mailto:a@b.c"],
+            vec!["x.org/a", "1http://x", "http://a b", "http:"],
+        ),
+        (
+            Format::Uuid,
+            vec!["123e4567-e89b-12d3-a456-426614174000"],
+            vec![
+                "123e4567e89b12d3a456426614174000",
+                "123e4567-e89b-12d3-a456-42661417400g",
+            ],
+        ),
+    ];
+    for (format, good, bad) in cases {
+        for s in good {
+            assert!(format.matches(s), "{format:?} should accept {s:?}");
+        }
+        for s in bad {
+            assert!(!format.matches(s), "{format:?} should reject {s:?}");
+        }
+    }
+    assert_eq!(Format::from_name("date-time"), Some(Format::DateTime));
+    assert_eq!(Format::from_name("hostname"), None);
+
+    // 长度按码点计
+    assert!(Check::MaxLength(2).accepts_str("中文"));
+    assert!(!Check::MinLength(3).accepts_str("中文"));
+    assert_eq!(Check::Format(Format::Email).describe(), r#"format "email""#);
+}
+
+#[test]
+fn test_numeric_checks() {
+    assert!(Check::Minimum(0.0).accepts_number(0.0));
+    assert!(!Check::ExclusiveMinimum(0.0).accepts_number(0.0));
+    assert!(Check::Maximum(100.0).accepts_number(100.0));
+    assert!(!Check::ExclusiveMaximum(100.0).accepts_number(100.0));
+    assert!(!Check::Maximum(100.0).accepts_number(f64::INFINITY));
+
+    // 浮点倍数按相对误差判断
+    assert!(Check::MultipleOf(0.1).accepts_number(0.3));
+    assert!(Check::MultipleOf(0.01).accepts_number(19.99));
+    assert!(!Check::MultipleOf(0.5).accepts_number(1.25));
+    assert!(Check::MultipleOf(3.0).accepts_number(-9.0));
+
+    // 数值约束不影响字符串，反之亦然
+    assert!(Check::Minimum(10.0).accepts_str("1"));
+    assert!(Check::MinLength(10).accepts_number(1.0));
+    assert_eq!(Check::Maximum(100.0).describe(), "maximum 100");
+    assert_eq!(Check::MultipleOf(0.5).describe(), "multipleOf 0.5");
+}
+
+#[test]
+fn test_array_checks() {
+    assert!(Check::MinItems(2).accepts_array(2, || true));
+    assert!(!Check::MinItems(2).accepts_array(1, || true));
+    assert!(!Check::MaxItems(4).accepts_array(5, || true));
+    assert!(!Check::UniqueItems.accepts_array(2, || false));
+
+    // 只有 uniqueItems 才会计算唯一性
+    assert!(Check::MaxItems(4).accepts_array(3, || unreachable!()));
+    assert!(Check::MinLength(10).accepts_array(0, || false));
+    assert_eq!(Check::MinItems(1).describe(), "minItems 1");
+    assert_eq!(Check::UniqueItems.describe(), "uniqueItems");
+}
+
+#[test]
+fn test_repair_json_str_output() {
+    let compact = repair::repair_json_str(
+        "```json\n{\"b\": 1, \"a\": [1.50, \"x\\ty\",], // note\n\"c\": NaN, \"d\": 123456789012345678901234567890",
+        None,
+    )
+    .expect("should repair");
+    assert_eq!(
+        compact,
+        r#"{"b":1,"a":[1.50,"x\ty"],"c":null,"d":123456789012345678901234567890}"#
+    );
+    // 浮点按原文写法输出，不合 JSON 语法的写法才规范化
+    assert_eq!(
+        repair::repair_json_str("[1e3, 2.5E-1, -0.0, .5, 1.]", None).unwrap(),
+        "[1e3,2.5E-1,-0.0,0.5,1.0]"
+    );
+    // 超出 i64 的整数同样去掉 '+' 与前导零，输出能被严格模式读回
+    let big = repair::repair_json_str(
+        "[00012345678901234567890123, -00012345678901234567890123, +12345678901234567890123]",
+        None,
+    )
+    .unwrap();
+    assert_eq!(
+        big,
+        "[12345678901234567890123,-12345678901234567890123,12345678901234567890123]"
+    );
+    assert!(repair::repair_json_with_options(&big, &RepairOptions::strict()).is_ok());
+
+    let pretty =
+        repair::repair_json_str("{\"a\": [1, {}], \"b\": []", Some(2)).expect("should repair");
+    assert_eq!(
+        pretty,
+        "{\n  \"a\": [\n    1,\n    {}\n  ],\n  \"b\": []\n}"
+    );
+}
+
+#[test]
+fn test_repair_report_events() {
+    let text = "Sure: {'a': True, \"b\": [1,,2,], // why\n \"c\": NaN,";
+    let (value, events) = repair::repair_json_with_report(text).expect("should repair");
+    assert_eq!(value.get("a"), Some(&JsonValue::Bool(true)));
+
+    let got: Vec<(RepairKind, usize)> = events.iter().map(|e| (e.kind, e.offset)).collect();
+    let comma = text.find(",,").unwrap();
+    assert_eq!(
+        got,
+        vec![
+            (RepairKind::SkippedPrefix, 6),
+            (RepairKind::SingleQuotedString, 7),
+            (RepairKind::PythonLiteral, 12),
+            (RepairKind::ExtraComma, comma + 1),
+            (RepairKind::TrailingComma, comma + 3),
+            (RepairKind::StrippedComment, text.find("//").unwrap()),
+            (RepairKind::NonFiniteNumber, text.find("NaN").unwrap()),
+            (RepairKind::TrailingComma, text.len() - 1),
+            (RepairKind::AutoClosedObject, text.len()),
+        ]
+    );
+
+    let (_, clean) = repair::repair_json_with_report(r#"{"a": [1, 2], "b": null}"#).unwrap();
+    assert!(clean.is_empty());
+
+    // 围栏本身与文档之后的文字都要报告
+    let kinds = |text: &str| -> Vec<(RepairKind, usize)> {
+        let (_, events) = repair::repair_json_with_report(text).unwrap();
+        events.iter().map(|e| (e.kind, e.offset)).collect()
+    };
+    assert_eq!(
+        kinds("```json\n{\"a\": 1}\n```"),
+        vec![
+            (RepairKind::StrippedFence, 0),
+            (RepairKind::StrippedFence, 17)
+        ]
+    );
+    assert_eq!(
+        kinds("Here:\n```json\n{\"a\": 1}\n```"),
+        vec![
+            (RepairKind::StrippedFence, 6),
+            (RepairKind::SkippedPrefix, 14),
+            (RepairKind::StrippedFence, 23)
+        ]
+    );
+    assert_eq!(
+        kinds(r#"{"a": 1} and then prose {"b": 2}"#),
+        vec![(RepairKind::TrailingContent, 9)]
+    );
+    assert_eq!(
+        kinds(r#"{"a": `1`}"#),
+        vec![
+            (RepairKind::StrippedFence, 6),
+            (RepairKind::StrippedFence, 8)
+        ]
+    );
+}
+
+#[test]
+fn test_repair_options_toggle_leniencies() {
+    let parse = |text: &str, options: RepairOptions| {
+        repair::repair_json_with_options(text, &options).map(|(value, _)| value.to_json())
+    };
+    let strict = RepairOptions::strict();
+    let default = RepairOptions::default();
+
+    // 严格模式就是 RFC 8259：语法之外的写法一律报错
+    assert_eq!(
+        parse(r#"{"a": [1, null]}"#, strict).unwrap(),
+        r#"{"a":[1,null]}"#
+    );
+
+    let cases = [
+        (
+            "{\"a\": 1 # note\n}",
+            RepairOptions {
+                allow_comments: false,
+                ..default
+            },
+        ),
+        (
+            "[1, ```note```\n 2]",
+            RepairOptions {
+                allow_code_fences: false,
+                ..default
+            },
+        ),
+        (
+            "{\"a\": `1}",
+            RepairOptions {
+                allow_code_fences: false,
+                ..default
+            },
+        ),
+        (
+            r#"{"a": None}"#,
+            RepairOptions {
+                allow_python_literals: false,
+                ..default
+            },
+        ),
+        (
+            r#"{"a": NaN}"#,
+            RepairOptions {
+                allow_non_finite: false,
+                ..default
+            },
+        ),
+        (
+            r#"{'a': 1}"#,
+            RepairOptions {
+                allow_single_quotes: false,
+                ..default
+            },
+        ),
+        (
+            r#"{"a": [1,],}"#,
+            RepairOptions {
+                allow_trailing_commas: false,
+                ..default
+            },
+        ),
+        (
+            r#"{"a": [1"#,
+            RepairOptions {
+                auto_close: false,
+                ..default
+            },
+        ),
+        (
+            r#"Sure: {"a": 1}"#,
+            RepairOptions {
+                scan_prefix: false,
+                ..default
+            },
+        ),
+        (
+            "[1] trailing",
+            RepairOptions {
+                allow_trailing_content: false,
+                ..default
+            },
+        ),
+        (
+            "[1e400]",
+            RepairOptions {
+                allow_non_finite: false,
+                ..default
+            },
+        ),
+    ];
+    let loose = RepairOptions {
+        allow_loose_tokens: false,
+        ..default
+    };
+    let cases = cases.into_iter().chain(
+        [
+            "[+1]",
+            "[01]",
+            "[.5]",
+            "[1.]",
+            "[\u{a0}1]",
+            "[\u{b}1]",
+            r#"["\q"]"#,
+            r#"["\u12"]"#,
+            "[\"a\nb\"]",
+            "[\"a\tb\"]",
+        ]
+        .map(|text| (text, loose)),
+    );
+    for (text, options) in cases {
+        assert!(
+            parse(text, default).is_ok(),
+            "default should repair {text:?}"
+        );
+        assert!(
+            parse(text, options).is_err(),
+            "{options:?} should reject {text:?}"
+        );
+        assert!(
+            parse(text, strict).is_err(),
+            "strict should reject {text:?}"
+        );
+    }
+
+    // 关掉 Python 字面量后，大写的 True 也不再接受；NaN 仍由 allow_non_finite 决定
+    let no_python = RepairOptions {
+        allow_python_literals: false,
+        ..default
+    };
+    let err = parse("[TRUE]", no_python).unwrap_err();
+    assert_eq!(err.kind(), &RepairErrorKind::InvalidLiteral);
+    assert_eq!(parse("[NaN]", no_python).unwrap(), "[null]");
+
+    let err = parse(r#"{"a": "open"#, strict).unwrap_err();
+    assert_eq!(err.kind(), &RepairErrorKind::UnexpectedEof);
+    assert_eq!(err.expected(), &["'\"'"]);
+}
+
+#[test]
+fn test_repair_unquoted_keys_opt_in() {
+    let text = fs::read_to_string("tests/failure/repair/unquoted_keys.txt").unwrap();
+    assert!(repair::repair_json(&text).is_err());
+
+    let options = RepairOptions {
+        allow_unquoted_keys: true,
+        ..RepairOptions::default()
+    };
+    let (value, events) = repair::repair_json_with_options(&text, &options).unwrap();
+    assert_eq!(
+        value.to_json(),
+        r#"{"key":"value","_underscore":123,"$dollar":true}"#
+    );
+    let unquoted: Vec<usize> = events
+        .iter()
+        .filter(|e| e.kind == RepairKind::UnquotedKey)
+        .map(|e| e.offset)
+        .collect();
+    assert_eq!(unquoted, vec![2, 16, 34]);
+
+    let (value, _) = repair::repair_json_with_options("{名称: 1, größe2 : 2}", &options).unwrap();
+    assert_eq!(value.to_json(), r#"{"名称":1,"größe2":2}"#);
+
+    // 只有后面跟着冒号才当作键；缺冒号或非标识符照样报错
+    for text in ["{key 1}", "{1: 2}", "{a-b: 1}"] {
+        let err = repair::repair_json_with_options(text, &options).unwrap_err();
+        assert_eq!(err.expected(), &["a string key", "'}'"], "{text:?}");
+    }
+}
+
+#[test]
+fn test_repair_insert_missing_commas_opt_in() {
+    let text = fs::read_to_string("tests/failure/repair/missing_comma.txt").unwrap();
+    assert!(repair::repair_json(&text).is_err());
+
+    let options = RepairOptions {
+        insert_missing_commas: true,
+        ..RepairOptions::default()
+    };
+    let repair = |text: &str| {
+        repair::repair_json_with_options(text, &options)
+            .map(|(value, events)| (value.to_json(), events))
+    };
+
+    let (json, events) = repair(&text).unwrap();
+    assert_eq!(json, "[1,2]");
+    assert_eq!(
+        events
+            .iter()
+            .map(|e| (e.kind, e.offset))
+            .collect::<Vec<_>>(),
+        vec![(RepairKind::MissingComma, 2)]
+    );
+
+    let (json, _) = repair("{\"a\": 1 \"b\": [true {}\n\"x\"] 'c': null}").unwrap();
+    assert_eq!(json, r#"{"a":1,"b":[true,{},"x"],"c":null}"#);
+
+    // 缺冒号依然拒绝，不瞎猜
+    let text = fs::read_to_string("tests/failure/repair/missing_colon.txt").unwrap();
+    assert_eq!(repair(&text).unwrap_err().expected(), &["':'"]);
+    let err = repair(r#"{"a": 1 "b" 2}"#).unwrap_err();
+    assert_eq!(err.expected(), &["':'"]);
+    let err = repair(r#"{"a": 1 b: 2}"#).unwrap_err();
+    assert_eq!(err.expected(), &["','", "'}'"]);
+}
+
+#[test]
+fn test_repair_size_limits() {
+    // 恶意深嵌套：干净地报错，而不是栈溢出
+    let hostile = "[".repeat(100_000);
+    let err = repair::repair_json(&hostile).unwrap_err();
+    assert_eq!(
+        err.kind(),
+        &RepairErrorKind::DepthLimit(repair::DEFAULT_MAX_DEPTH)
+    );
+    assert_eq!(err.offset(), repair::DEFAULT_MAX_DEPTH);
+
+    let at_limit = format!("{}{}", "[".repeat(128), "]".repeat(128));
+    assert!(repair::repair_json(&at_limit).is_ok());
+
+    let options = RepairOptions {
+        max_depth: 2,
+        max_string_len: 4,
+        max_input_len: 32,
+        ..RepairOptions::default()
+    };
+    let repair = |text: &str| repair::repair_json_with_options(text, &options).map(|_| ());
+    assert!(repair(r#"{"a": ["abcd"]}"#).is_ok());
+    assert_eq!(
+        repair(r#"{"a": [[1]]}"#).unwrap_err().kind(),
+        &RepairErrorKind::DepthLimit(2)
+    );
+    let err = repair(r#"{"a": "abc\"de"}"#).unwrap_err();
+    assert_eq!(err.kind(), &RepairErrorKind::StringTooLong(4));
+    assert_eq!(err.offset(), 6);
+    assert_eq!(
+        repair(r#"note: {"a": "abcdefgh"#).unwrap_err().kind(),
+        &RepairErrorKind::StringTooLong(4)
+    );
+    assert_eq!(
+        repair(&" ".repeat(33)).unwrap_err().kind(),
+        &RepairErrorKind::InputTooLarge(32)
+    );
+}
+
+#[test]
+fn test_repair_json_all_documents() {
+    let text = "Plan: {\"step\": 1} then [1, 2,] broken {\"a\" 1} done {'ok': true";
+    let docs = repair::repair_json_all(text).expect("should scan");
+    let got: Vec<(String, &str)> = docs
+        .iter()
+        .map(|d| (d.value.to_json(), &text[d.start..d.end]))
+        .collect();
+    assert_eq!(
+        got,
+        vec![
+            (r#"{"step":1}"#.to_string(), r#"{"step": 1}"#),
+            ("[1,2]".to_string(), "[1, 2,]"),
+            (r#"{"ok":true}"#.to_string(), "{'ok': true"),
+        ]
+    );
+    assert_eq!(docs[1].events[0].kind, RepairKind::TrailingComma);
+    assert_eq!(docs[1].events[0].offset, text.find("2,]").unwrap() + 1);
+
+    // 嵌套的容器属于外层文档，不会重复返回
+    let docs = repair::repair_json_all(r#"{"a": [1]} {"b": {}}"#).unwrap();
+    assert_eq!(docs.len(), 2);
+    assert!(repair::repair_json_all("no json here").unwrap().is_empty());
+}
+
+#[test]
+fn test_repair_value_at_stops_after_value() {
+    let text = r#"{"meta": {'k': [1, 2,], "n": None}, "next": 1}"#;
+    let start = text.find("{'k'").unwrap();
+    let (value, end) =
+        repair::repair_value_at(text, start, &RepairOptions::default()).expect("should repair");
+    assert_eq!(value.to_json(), r#"{"k":[1,2],"n":null}"#);
+    assert_eq!(&text[end..], r#", "next": 1}"#);
+
+    let (value, end) = repair::repair_value_at(" 42 rest", 0, &RepairOptions::default()).unwrap();
+    assert_eq!((value, end), (JsonValue::Number(Number::Int(42)), 3));
+
+    // 不跳过前缀：起点不是值就报错
+    let err = repair::repair_value_at("note {}", 0, &RepairOptions::default()).unwrap_err();
+    assert_eq!(err.offset(), 0);
+}
+
+#[test]
+fn test_repair_error_location() {
+    let err = repair::repair_json("{\n  \"key\" 1}").unwrap_err();
+    assert_eq!(err.kind(), &RepairErrorKind::UnexpectedChar('1'));
+    assert_eq!(err.expected(), &["':'"]);
+    assert_eq!((err.offset(), err.line(), err.column()), (10, 2, 9));
+    assert!(err.snippet().contains("\"key\" 1"));
+    assert_eq!(
+        err.to_string(),
+        "Unexpected character '1', expected ':': line 2 column 9 (byte 10)"
+    );
+
+    // 单独的 '/' 不是注释，错误指向它本身
+    let err = repair::repair_json(r#"{"a": 1 / 2}"#).unwrap_err();
+    assert_eq!(err.kind(), &RepairErrorKind::UnexpectedChar('/'));
+    assert_eq!(err.offset(), 8);
+
+    let err = repair::repair_json("no json here").unwrap_err();
+    assert_eq!(err.kind(), &RepairErrorKind::NoJsonFound);
+}
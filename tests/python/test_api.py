@@ -1,6 +1,15 @@
+import json
+
 import pytest
 
-from llm_json_utils import JsonExtractor, repair_json
+from llm_json_utils import (
+    JsonExtractor,
+    JsonRepairError,
+    repair_json,
+    repair_json_all,
+    repair_json_report,
+    repair_json_str,
+)
 
 
 def test_repair_json_trailing_comma_and_comments():
@@ -50,3 +59,794 @@ def test_schema_extractor_missing_required_field_raises():
     extractor = JsonExtractor(schema)
     with pytest.raises(ValueError):
         extractor.extract(b"{'score': 10}")
+
+
+def test_repair_json_str_emits_valid_json():
+    text = repair_json_str('{"b": 1, "a": [1, 2,], // note\n "c": "x')
+    assert text == '{"b":1,"a":[1,2],"c":"x"}'
+    assert json.loads(text) == {"b": 1, "a": [1, 2], "c": "x"}
+    big = repair_json_str("[00012345678901234567890123, -0001]")
+    assert json.loads(big) == [12345678901234567890123, -1]
+
+    pretty = repair_json_str('{"a": [1]}', pretty=True, indent=4)
+    assert pretty == '{\n    "a": [\n        1\n    ]\n}'
+
+
+def test_repair_json_report_lists_fixes():
+    text = "Answer → {'a': True, \"b\": [1, 2,]"
+    data, events = repair_json_report(text)
+    assert data == {"a": True, "b": [1, 2]}
+    kinds = [e["kind"] for e in events]
+    assert kinds == [
+        "SkippedPrefix",
+        "SingleQuotedString",
+        "PythonLiteral",
+        "TrailingComma",
+        "AutoClosedObject",
+    ]
+    # offset 是 str 下标，可以直接切片
+    assert text[events[0]["offset"]] == "{"
+    assert events[0]["byte_offset"] == len(text[: events[0]["offset"]].encode())
+
+    _, clean = repair_json_report('{"a": 1}')
+    assert clean == []
+
+
+def test_repair_options_as_keyword_arguments():
+    text = '{"a": NaN, "b": None}'
+    with pytest.raises(JsonRepairError):
+        repair_json(text, allow_non_finite=False)
+    with pytest.raises(JsonRepairError):
+        repair_json(text, strict=True)
+    # strict 只是起点，单项仍可再打开
+    assert repair_json_str(text, strict=True, allow_non_finite=True, allow_python_literals=True) == (
+        '{"a":null,"b":null}'
+    )
+    _, events = repair_json_report("Sure: {'a': 1}", allow_comments=False)
+    assert [e["kind"] for e in events] == ["SkippedPrefix", "SingleQuotedString"]
+    with pytest.raises(JsonRepairError):
+        repair_json("[1] done", strict=True)
+    assert repair_json("[+1] done", strict=True, allow_trailing_content=True, allow_loose_tokens=True) == [1]
+    for text in ['{"a":`1}', '`{"a": 1}`', '["x\ny"]']:
+        with pytest.raises(JsonRepairError):
+            repair_json(text, strict=True)
+    with pytest.raises(TypeError):
+        repair_json("{}", allow_everything=True)
+    # 第一个参数在四个函数里都叫 text，也能按关键字传
+    assert repair_json(text="[1,]") == [1]
+    assert repair_json_str(text="[1,]") == "[1]"
+    assert repair_json_report(text="[1]") == ([1], [])
+    assert [d["value"] for d in repair_json_all(text="[1]")] == [[1]]
+
+
+def test_repair_unquoted_keys_are_opt_in():
+    text = "{name: 'x', 年龄: 3}"
+    with pytest.raises(JsonRepairError):
+        repair_json(text)
+    data, events = repair_json_report(text, allow_unquoted_keys=True)
+    assert data == {"name": "x", "年龄": 3}
+    assert [e["offset"] for e in events if e["kind"] == "UnquotedKey"] == [1, 12]
+
+
+def test_repair_insert_missing_commas_is_opt_in():
+    with pytest.raises(JsonRepairError):
+        repair_json('{"a": 1 "b": 2}')
+    assert repair_json('{"a": 1 "b": [1 2]}', insert_missing_commas=True) == {"a": 1, "b": [1, 2]}
+    with pytest.raises(JsonRepairError):
+        repair_json('{"a": 1 "b" 2}', insert_missing_commas=True)
+
+
+def test_repair_limits_raise_cleanly():
+    with pytest.raises(RecursionError):
+        repair_json("[" * 100_000)
+    with pytest.raises(RecursionError):
+        repair_json("[[[1]]]", max_depth=2)
+    with pytest.raises(JsonRepairError):
+        repair_json('{"a": "' + "x" * 10 + '"}', max_string_len=8)
+    with pytest.raises(JsonRepairError):
+        repair_json("[1, 2, 3]", max_input_len=4)
+    assert repair_json("[[1]]", max_depth=2) == [[1]]
+
+
+def test_repair_json_all_returns_spans():
+    text = "计划：{'step': 1}，结果：[1, 2,]"
+    docs = repair_json_all(text)
+    assert [d["value"] for d in docs] == [{"step": 1}, [1, 2]]
+    assert [text[d["start"] : d["end"]] for d in docs] == ["{'step': 1}", "[1, 2,]"]
+    raw = text.encode()
+    assert raw[docs[1]["byte_start"] : docs[1]["byte_end"]] == "[1, 2,]".encode()
+    assert [e["kind"] for e in docs[0]["events"]] == ["SingleQuotedString"]
+    [comma] = docs[1]["events"]
+    assert comma["kind"] == "TrailingComma"
+    assert text[comma["offset"]] == ","
+    assert repair_json_all("nothing") == []
+
+
+def test_schema_extractor_extract_all():
+    extractor = JsonExtractor(
+        {
+            "type": "object",
+            "properties": {"summary": {"type": "string"}},
+            "required": ["summary"],
+        }
+    )
+    blob = b"plan {'summary': 'a'} noise {} result {\"summary\": \"b\"}"
+    matches = extractor.extract_all(blob)
+    assert [m["value"] for m in matches] == [{"summary": "a"}, {"summary": "b"}]
+    assert [blob[m["start"] : m["end"]] for m in matches] == [
+        b"{'summary': 'a'}",
+        b'{"summary": "b"}',
+    ]
+    assert extractor.extract_all(b"nothing here") == []
+
+
+def test_schema_extractor_spans():
+    extractor = JsonExtractor(
+        {
+            "type": "object",
+            "properties": {
+                "summary": {"type": "string"},
+                "tags": {"type": "array", "items": {"type": "string"}},
+                "a/b": {"type": "number"},
+            },
+            "required": ["summary"],
+        }
+    )
+    blob = 'Result: {"summary": "完成", "tags": ["x", "y"], "a/b": 2} bye'.encode()
+    found = extractor.extract_span(blob)
+    assert found["value"]["summary"] == "完成"
+    assert blob[found["start"] : found["end"]] == '{"summary": "完成", "tags": ["x", "y"], "a/b": 2}'.encode()
+    assert found["fields"] is None
+
+    found = extractor.extract_span(blob, fields=True)
+    spans = {k: blob[s:e].decode() for k, (s, e) in found["fields"].items()}
+    assert spans["/summary"] == '"完成"'
+    assert spans["/tags"] == '["x", "y"]'
+    assert spans["/tags/1"] == '"y"'
+    assert spans["/a~1b"] == "2"
+    assert spans[""] == blob[found["start"] : found["end"]].decode()
+    # 剥离 JSON 后剩下的正文
+    assert blob[: found["start"]] + blob[found["end"] :] == b"Result:  bye"
+
+
+def test_schema_extractor_root_array_and_primitives():
+    entities = JsonExtractor(
+        {
+            "type": "array",
+            "items": {"type": "object", "properties": {"name": {"type": "string"}}},
+        }
+    )
+    blob = b"Entities found: [{'name': 'Ada'}, {\"name\": \"Linus\"}] done"
+    assert entities.extract(blob) == [{"name": "Ada"}, {"name": "Linus"}]
+    assert entities.extract_span(blob)["start"] == blob.index(b"[")
+
+    assert JsonExtractor({"type": "number"}).extract(b"Score: -12.5 points") == -12.5
+    assert JsonExtractor({"type": "string"}).extract(b"Title - 'Hello'") == "Hello"
+    # 根字符串两边都是正文：第一个未转义的闭合引号就是结束
+    assert JsonExtractor({"type": "string"}).extract(b'say "hi" now') == "hi"
+    assert JsonExtractor({"type": "string"}).extract(b'answer: "hi".') == "hi"
+    assert JsonExtractor({"type": "string"}).extract(b'quote: "a \\"b\\" c", done') == 'a "b" c'
+    assert JsonExtractor({"type": "boolean"}).extract(b"Answer: False.") is False
+    # 只在词首开始匹配
+    assert JsonExtractor({"type": "boolean"}).extract(b"untrue: false") is False
+    assert JsonExtractor({"type": "number"}).extract(b"model v2 scored 7") == 7.0
+    # 也不从更长数字的中间开始：3.5 被整数拒绝后，不会拿它的小数部分当答案
+    assert JsonExtractor({"type": "integer"}).extract(b"v2 is 3.5 and 7") == 7
+    assert JsonExtractor({"type": "integer"}).extract(b"rate 1e-5, then 9") == 9
+    with pytest.raises(JsonRepairError) as exc_info:
+        JsonExtractor({"type": "array", "items": {"type": "number"}}).extract(b"no list")
+    assert exc_info.value.expected == ["'['"]
+
+
+def test_schema_extractor_integer_fields():
+    extractor = JsonExtractor(
+        {
+            "type": "object",
+            "properties": {
+                "count": {"type": "integer"},
+                "ids": {"type": "array", "items": {"type": "integer"}},
+                "score": {"type": "number"},
+            },
+            "required": ["count"],
+        }
+    )
+    data = extractor.extract(b'{"count": 1,000, "ids": [+5,1e3,2.50e1,-0], "score": 3}')
+    assert data == {"count": 1000, "ids": [5, 1000, 25, 0], "score": 3.0}
+    assert all(type(v) is int for v in [data["count"], *data["ids"]])
+
+    big = extractor.extract(b'{"count": 123456789012345678901234567890}')
+    assert big["count"] == 123456789012345678901234567890
+
+    with pytest.raises(JsonRepairError) as exc_info:
+        extractor.extract(b'{"count": 2.5}')
+    assert exc_info.value.expected == ["an integer"]
+    assert exc_info.value.pos == len(b'{"count": ')
+
+    # 数组里的逗号只是分隔符；带引号的数字与带引号的布尔一样接受
+    data = extractor.extract(b'{"count": "12", "ids": [1,250,999]}')
+    assert data == {"count": 12, "ids": [1, 250, 999]}
+    numbers = JsonExtractor({"type": "array", "items": {"type": "number"}})
+    assert numbers.extract(b"[1,250,999]") == [1.0, 250.0, 999.0]
+    with pytest.raises(JsonRepairError) as exc_info:
+        extractor.extract(b'{"count": "many"}')
+    assert (exc_info.value.msg, exc_info.value.expected) == ("Unexpected value, expected an integer", ["an integer"])
+
+
+def test_schema_extractor_null_handling():
+    extractor = JsonExtractor(
+        {
+            "type": "object",
+            "properties": {
+                "name": {"type": ["string", "null"]},
+                "age": {"type": "integer"},
+                "ok": {"type": "boolean"},
+                "meta": {"type": ["object", "null"], "properties": {"k": {"type": "string"}}},
+                "nothing": {"type": "null"},
+            },
+            "required": ["name"],
+        }
+    )
+    data = extractor.extract(b'{"name": None, "age": null, "ok": "true", "meta": null, "nothing": null}')
+    assert data == {"name": None, "age": None, "ok": True, "meta": None, "nothing": None}
+    # 带引号的 "null" 是字符串；nullable 的对象仍然正常解析
+    data = extractor.extract(b'{"name": "null", "meta": {"k": "v"}}')
+    assert data == {"name": "null", "meta": {"k": "v"}}
+
+    with pytest.raises(JsonRepairError) as exc_info:
+        extractor.extract(b'{"name": "x", "ok": maybe}')
+    assert exc_info.value.expected == ["true", "false"]
+    with pytest.raises(JsonRepairError):
+        extractor.extract(b'{"name": "x", "nothing": 1}')
+    # 必填且不可为 null 的标量遇到裸 null 报错，与 nullable 区分开
+    strict_age = JsonExtractor(
+        {"type": "object", "properties": {"age": {"type": "integer"}}, "required": ["age"]}
+    )
+    with pytest.raises(JsonRepairError) as exc_info:
+        strict_age.extract(b'{"age": null}')
+    assert exc_info.value.expected == ["an integer"]
+    # 被拒的候选整体跳过，不把它里面嵌套的对象当成匹配
+    nested = JsonExtractor(
+        {
+            "type": "object",
+            "properties": {"age": {"type": "integer"}, "ok": {"type": "boolean"}},
+            "required": ["age"],
+        }
+    )
+    for blob in [b'{"age": null, "pet": {"age": 3}}', b'{"age": 1, "ok": maybe, "sub": {"age": 2, "ok": true}}']:
+        with pytest.raises(JsonRepairError):
+            nested.extract(blob)
+    with pytest.raises(ValueError):
+        JsonExtractor({"type": ["string", "number"]})
+
+
+def test_schema_extractor_enum_and_const():
+    schema = {
+        "type": "object",
+        "properties": {
+            "label": {"enum": ["positive", "negative", "neutral"]},
+            "version": {"const": 2},
+            "level": {"type": "integer", "enum": [1, 2, 3]},
+        },
+        "required": ["label"],
+    }
+    strict = JsonExtractor(schema)
+    assert strict.extract(b'{"label": "neutral", "version": 2, "level": 3}') == {
+        "label": "neutral",
+        "version": 2,
+        "level": 3,
+    }
+    with pytest.raises(JsonRepairError) as exc_info:
+        strict.extract(b'{"label": "Positive"}')
+    assert exc_info.value.expected == ['"positive"', '"negative"', '"neutral"']
+    with pytest.raises(JsonRepairError):
+        strict.extract(b'{"label": "neutral", "level": 7}')
+    # 被拒绝的对象里嵌套的对象不是答案，后面另一段 JSON 仍然可以匹配
+    with pytest.raises(JsonRepairError):
+        strict.extract(b'{"label": "mixed", "detail": {"label": "negative"}}')
+    assert strict.extract(b'{"label": "mixed"} then {"label": "negative"}') == {"label": "negative"}
+
+    fuzzy = JsonExtractor(schema, fuzzy_enums=True)
+    assert fuzzy.extract('{"label": " ＰＯＳＩＴＩＶＥ "}'.encode()) == {"label": "positive"}
+    with pytest.raises(JsonRepairError):
+        fuzzy.extract(b'{"label": "mixed"}')
+
+
+def test_schema_extractor_any_of_union():
+    circle = {
+        "type": "object",
+        "properties": {"kind": {"const": "circle"}, "radius": {"type": "number"}},
+        "required": ["kind"],
+    }
+    rect = {
+        "type": "object",
+        "properties": {
+            "kind": {"const": "rect"},
+            "w": {"type": "number"},
+            "h": {"type": "number"},
+        },
+        "required": ["kind"],
+    }
+    # 每个备选都用字符串 const 固定了 kind，自动识别为判别字段
+    shapes = JsonExtractor({"oneOf": [circle, rect]})
+    assert shapes.extract(b'shape: {"w": 2, "kind": "rect", "h": 3}') == {
+        "kind": "rect",
+        "w": 2.0,
+        "h": 3.0,
+    }
+    assert shapes.extract(b"{'radius': 1.5, 'kind': 'circle'}") == {
+        "kind": "circle",
+        "radius": 1.5,
+    }
+    # 嵌套对象里的同名字段不参与判别
+    nested = JsonExtractor(
+        {
+            "type": "object",
+            "properties": {"shape": {"anyOf": [circle, rect]}, "kind": {"type": "string"}},
+        }
+    )
+    assert nested.extract(b'{"shape": {"kind": "circle", "radius": 1}, "kind": "rect"}') == {
+        "shape": {"kind": "circle", "radius": 1.0},
+        "kind": "rect",
+    }
+
+    # 没有判别字段时取锚定字段最多的备选
+    loose = JsonExtractor(
+        {
+            "anyOf": [
+                {"type": "object", "properties": {"name": {"type": "string"}}},
+                {
+                    "type": "object",
+                    "properties": {"name": {"type": "string"}, "age": {"type": "integer"}},
+                },
+            ]
+        }
+    )
+    assert loose.extract(b'{"name": "x", "age": 3}') == {"name": "x", "age": 3}
+    scalars = JsonExtractor({"anyOf": [{"type": "integer"}, {"type": "string"}]})
+    assert scalars.extract(b'answer: "seven"') == "seven"
+    assert scalars.extract(b"answer: 7") == 7
+    # 开头对得上的备选优先于未加引号的字符串兜底，与备选顺序无关
+    flipped = JsonExtractor({"anyOf": [{"type": "string"}, {"type": "integer"}]})
+    assert flipped.extract(b"answer: 42") == 42
+    flags = JsonExtractor({"anyOf": [{"type": "string"}, {"type": "boolean"}]})
+    assert flags.extract(b"answer: true") is True
+
+    # pydantic 的 Optional[Model]：null 不交给对象备选
+    optional = JsonExtractor(
+        {
+            "type": "object",
+            "properties": {
+                "user": {"anyOf": [{"$ref": "#/$defs/User"}, {"type": "null"}]},
+                "name": {"type": "string"},
+            },
+            "$defs": {
+                "User": {
+                    "type": "object",
+                    "properties": {"name": {"type": "string"}},
+                    "required": ["name"],
+                }
+            },
+        }
+    )
+    assert optional.extract(b'{"user": null, "name": "outer"}') == {"user": None, "name": "outer"}
+
+    # 递归 anyOf 没有判别字段：每层的备选共享同一批子值，深层嵌套也不能指数级重复试探
+    branch = {"type": "array", "items": {"$ref": "#/$defs/N"}}
+    tree = JsonExtractor(
+        {
+            "$defs": {
+                "N": {
+                    "anyOf": [
+                        {
+                            "type": "object",
+                            "properties": {"c": branch, "x": {"type": "integer"}},
+                        },
+                        {
+                            "type": "object",
+                            "properties": {"c": branch, "y": {"type": "string"}},
+                        },
+                    ]
+                }
+            },
+            "$ref": "#/$defs/N",
+        }
+    )
+    depth = 40
+    blob = '{"c": [' * depth + '{"y": "leaf"}' + "]}" * depth
+    expected = {"y": "leaf"}
+    for _ in range(depth):
+        expected = {"c": [expected]}
+    assert tree.extract(blob.encode()) == expected
+
+
+def test_schema_extractor_resolves_refs():
+    # Pydantic 风格：嵌套模型放在 $defs，评论树递归引用自身
+    schema = {
+        "$defs": {
+            "Author": {
+                "type": "object",
+                "properties": {"name": {"type": "string"}},
+            },
+            "Comment": {
+                "type": "object",
+                "properties": {
+                    "author": {"$ref": "#/$defs/Author"},
+                    "text": {"type": "string"},
+                    "replies": {"type": "array", "items": {"$ref": "#/$defs/Comment"}},
+                    "parent": {"anyOf": [{"$ref": "#/$defs/Comment"}, {"type": "null"}]},
+                },
+                "required": ["text"],
+            },
+        },
+        "$ref": "#/$defs/Comment",
+    }
+    extractor = JsonExtractor(schema)
+    blob = (
+        b'thread: {"author": {"name": "a"}, "text": "root", "replies": ['
+        b'{"text": "r1", "replies": [{"text": "r1.1", "replies": []}]},'
+        b'{"text": "r2", "parent": null}]}'
+    )
+    assert extractor.extract(blob) == {
+        "author": {"name": "a"},
+        "text": "root",
+        "replies": [
+            {"text": "r1", "replies": [{"text": "r1.1", "replies": []}]},
+            {"text": "r2", "parent": None},
+        ],
+    }
+
+    legacy = JsonExtractor(
+        {
+            "definitions": {"Node": {"type": "object", "properties": {"next": {"$ref": "#"}}}},
+            "type": "object",
+            "properties": {"head": {"$ref": "#/definitions/Node"}},
+        }
+    )
+    assert legacy.extract(b'{"head": {"next": {"head": {}}}}') == {
+        "head": {"next": {"head": {}}}
+    }
+
+    for bad in (
+        {"$ref": "#/$defs/Missing"},
+        {"$ref": "other.json#/Node"},
+        {"$defs": {"Loop": {"anyOf": [{"$ref": "#/$defs/Loop"}]}}, "$ref": "#/$defs/Loop"},
+    ):
+        with pytest.raises(ValueError):
+            JsonExtractor(bad)
+    with pytest.raises(ValueError) as exc_info:
+        JsonExtractor({"$ref": "#/$defs/Missing"})
+    assert str(exc_info.value) == "Invalid schema: Unsupported $ref: #/$defs/Missing"
+
+
+def test_schema_extractor_any_keeps_free_form_values():
+    extractor = JsonExtractor(
+        {
+            "type": "object",
+            "properties": {
+                "tool": {"type": "string"},
+                "arguments": {},
+                "meta": {"type": "mystery"},
+            },
+        }
+    )
+    blob = (
+        b"call: {\"tool\": \"search\", \"arguments\": {'q': 'rust', 'top': [1, 2,], "
+        b"'safe': True, // note\n 'extra': None}, \"meta\": \"v2\"}"
+    )
+    assert extractor.extract(blob) == {
+        "tool": "search",
+        "arguments": {"q": "rust", "top": [1, 2], "safe": True, "extra": None},
+        "meta": "v2",
+    }
+    assert extractor.extract(b'{"tool": "t", "arguments": 3.5, "meta": [1, {"a": null}]}') == {
+        "tool": "t",
+        "arguments": 3.5,
+        "meta": [1, {"a": None}],
+    }
+    # 与提取器一样容忍未加引号的键和漏写的逗号
+    tool_call = JsonExtractor(
+        {
+            "type": "object",
+            "properties": {"name": {"type": "string"}, "args": {}, "n": {"type": "integer"}},
+            "required": ["name"],
+        }
+    )
+    assert tool_call.extract(b'{"name":"f","args":{"x": 1 "y": 2}}') == {"name": "f", "args": {"x": 1, "y": 2}}
+    assert tool_call.extract(b'{"name": "f", "args": {"a": 1, b: 2}, "n": 1}') == {
+        "name": "f",
+        "args": {"a": 1, "b": 2},
+        "n": 1,
+    }
+    # 修不好的值不影响其他字段：标量按未加引号的字符串取一段，容器整段跳过取 None
+    assert extractor.extract(b'{"meta": hello world, "tool": "t"}') == {"meta": "hello", "tool": "t"}
+    assert extractor.extract(b'{"arguments": , "tool": "t"}') == {"arguments": None, "tool": "t"}
+    assert tool_call.extract(b'{"args": {"a" 1}, "name": "f"}') == {"args": None, "name": "f"}
+
+    anything = JsonExtractor({})
+    assert anything.extract(b'result: [1, "two", {"three": 3}] done') == [1, "two", {"three": 3}]
+    with pytest.raises(JsonRepairError):
+        anything.extract(b'{"a" 1}')
+
+
+def test_schema_extractor_additional_properties():
+    base = {"type": "object", "properties": {"name": {"type": "string"}}}
+    blob = b'{"name": "x", "age": 3, \'tags\': ["a", "b"], "nested": {"id": 1}}'
+
+    # 缺省与 false 相同：未知键丢弃
+    assert JsonExtractor(base).extract(blob) == {"name": "x"}
+    assert JsonExtractor({**base, "additionalProperties": False}).extract(blob) == {"name": "x"}
+
+    assert JsonExtractor({**base, "additionalProperties": True}).extract(blob) == {
+        "name": "x",
+        "age": 3,
+        "tags": ["a", "b"],
+        "nested": {"id": 1},
+    }
+
+    counts = JsonExtractor(
+        {"type": "object", "additionalProperties": {"type": "integer"}}
+    )
+    assert counts.extract(b'counts: {"apples": 3, "pears": 1,000}') == {
+        "apples": 3,
+        "pears": 1000,
+    }
+    with pytest.raises(JsonRepairError):
+        counts.extract(b'{"apples": 2.5}')
+    # 成员之间夹着注释或噪声时，后面的未知键照样保留
+    assert counts.extract(b'{"a": 2, /* c */ "y": 3, note "z": 4}') == {"a": 2, "y": 3, "z": 4}
+
+    found = JsonExtractor({**base, "additionalProperties": True}).extract_span(
+        b'{"name": "x", "extra": [1]}', fields=True
+    )
+    assert found["fields"]["/extra"] == (23, 26)
+
+
+def test_schema_extractor_keys_stay_in_their_object():
+    # 值不是对象时，嵌套 Schema 不会拿走外层的键；可选字段的裸 null 按没填处理
+    schema = {
+        "type": "object",
+        "properties": {
+            "user": {"type": "object", "properties": {"name": {"type": "string"}}},
+            "name": {"type": "string"},
+        },
+        "required": ["name"],
+    }
+    blob = b'{"user": null, "name": "outer"}'
+    assert JsonExtractor(schema).extract(blob) == {"user": None, "name": "outer"}
+    tags = JsonExtractor(
+        {
+            "type": "object",
+            "properties": {
+                "tags": {"type": "array", "items": {"type": "string"}},
+                "name": {"type": "string"},
+            },
+        }
+    )
+    with pytest.raises(JsonRepairError) as exc_info:
+        tags.extract(b'{"tags": "x", "name": "n"}')
+    assert exc_info.value.expected == ["'['"]
+    outer = JsonExtractor({**schema, "required": ["user", "name"]})
+    with pytest.raises(JsonRepairError) as exc_info:
+        outer.extract(blob)
+    assert exc_info.value.expected == ["'{'"]
+
+    extractor = JsonExtractor(
+        {
+            "type": "object",
+            "properties": {"name": {"type": "string"}, "score": {"type": "number"}},
+        }
+    )
+    # 嵌套对象、字符串里的同名键，以及后面另一段 JSON / 正文里的键都不会被抢过来
+    assert extractor.extract(
+        b'{"meta": {"name": "inner", "score": 1}, "note": "\'score\': 9", "name": "outer"}'
+    ) == {"name": "outer"}
+    assert extractor.extract(b'{"name": "a"} later {"score": 5}') == {"name": "a"}
+    assert extractor.extract(b'{"name": "a"} and it\'s "score": 7 in prose') == {"name": "a"}
+
+    found = extractor.extract_all(b'{"name": "a"} {"score": 5}')
+    assert [m["value"] for m in found] == [{"name": "a"}, {"score": 5.0}]
+
+    # 噪声之后的 '}' 也算这个对象的结尾
+    span = extractor.extract_span(b'{"score": 1, noise } tail')
+    assert (span["value"], span["end"]) == ({"score": 1.0}, 20)
+
+    # 截断的输入没有 '}'，在窗口内照常搜索
+    assert extractor.extract(b'{"score": 3, oops "name": "a"') == {"name": "a", "score": 3.0}
+
+
+def test_schema_extractor_decodes_escapes_like_repair():
+    extractor = JsonExtractor({"type": "object", "properties": {"text": {"type": "string"}}})
+    for raw in (
+        r'"line1\nline2\t\"q\" \u00e9"',
+        r'"\ud83d\ude00 \ud83d alone"',
+        r'"C:\temp\q \uZZ"',
+        r"'it\'s'",
+    ):
+        doc = "{\"text\": " + raw + "}"
+        assert extractor.extract(doc.encode()) == repair_json(doc), raw
+    assert extractor.extract(b'{"text": "caf\\u00e9"}') == {"text": "caf\u00e9"}
+
+
+def test_schema_extractor_string_constraints():
+    user = {
+        "type": "object",
+        "properties": {
+            "email": {"type": "string", "format": "email"},
+            "nick": {"type": ["string", "null"], "minLength": 2, "maxLength": 8},
+        },
+    }
+    extractor = JsonExtractor(
+        {
+            "type": "object",
+            "properties": {
+                "ids": {"type": "array", "items": {"type": "string", "format": "uuid"}},
+                "sku": {"type": "string", "pattern": "^[A-Z]{3}-\\d+$"},
+                "when": {"type": "string", "format": "date-time"},
+                "site": {"type": "string", "format": "hostname"},
+            },
+        }
+    )
+    good = (
+        b'{"ids": ["123e4567-e89b-12d3-a456-426614174000"], "sku": "ABC-42", '
+        b'"when": "2024-05-01T12:00:00Z", "site": "not checked"}'
+    )
+    assert extractor.extract(good)["sku"] == "ABC-42"
+    users = JsonExtractor({"type": "array", "items": user})
+    assert users.extract(b'[{"email": "a@b.io", "nick": null}, {"nick": "bob"}]') == [
+        {"email": "a@b.io", "nick": None},
+        {"nick": "bob"},
+    ]
+
+    cases = [
+        (users, b'[{"email": "nope"}]', b'"nope"', "/0/email", 'format "email"'),
+        (users, b'[{}, {"nick": "x"}]', b'"x"', "/1/nick", "minLength 2"),
+        (users, b'[{"nick": "much too long"}]', b'"much', "/0/nick", "maxLength 8"),
+        (extractor, b'{"ids": ["123e4567-e89b-12d3-a456-426614174000", "x"]}', b'"x"', "/ids/1", 'format "uuid"'),
+        (extractor, b'{"sku": "abc-42"}', b'"abc', "/sku", 'pattern "^[A-Z]{3}-\\d+$"'),
+        (extractor, b'{"when": "2024-05-01"}', b'"2024', "/when", 'format "date-time"'),
+        (JsonExtractor(user), b'{"email": "nope", "contact": {"email": "a@b.io"}}', b'"nope"', "/email", 'format "email"'),
+    ]
+    for schema, blob, bad, path, expected in cases:
+        with pytest.raises(JsonRepairError) as exc_info:
+            schema.extract(blob)
+        found = (exc_info.value.path, exc_info.value.expected, exc_info.value.pos)
+        assert found == (path, [expected], blob.index(bad)), blob
+        assert path in exc_info.value.msg
+
+    with pytest.raises(ValueError):
+        JsonExtractor({"type": "string", "pattern": "("})
+
+
+def test_schema_extractor_numeric_constraints():
+    extractor = JsonExtractor(
+        {
+            "type": "object",
+            "properties": {
+                "score": {"type": "number", "minimum": 0, "maximum": 100},
+                "ratio": {"type": "number", "exclusiveMinimum": 0, "exclusiveMaximum": 1},
+                "price": {"type": "number", "multipleOf": 0.01},
+                "count": {"type": ["integer", "null"], "minimum": 1, "multipleOf": 2},
+                "legacy": {"type": "integer", "maximum": 10, "exclusiveMaximum": True},
+            },
+        }
+    )
+    assert extractor.extract(
+        b'{"score": 100, "ratio": 0.5, "price": 19.99, "count": 4, "legacy": 9}'
+    ) == {"score": 100.0, "ratio": 0.5, "price": 19.99, "count": 4, "legacy": 9}
+    assert extractor.extract(b'{"count": null}') == {"count": None}
+
+    cases = [
+        (b'{"score": 950}', "/score", "maximum 100"),
+        (b'{"score": 950, "detail": {"score": 5}}', "/score", "maximum 100"),
+        (b'{"score": -1 %}', "/score", "minimum 0"),
+        (b'{"ratio": 0}', "/ratio", "exclusiveMinimum 0"),
+        (b'{"ratio": 1.0}', "/ratio", "exclusiveMaximum 1"),
+        (b'{"price": 1.005}', "/price", "multipleOf 0.01"),
+        (b'{"count": 3}', "/count", "multipleOf 2"),
+        (b'{"count": 0}', "/count", "minimum 1"),
+        (b'{"legacy": 10}', "/legacy", "exclusiveMaximum 10"),
+    ]
+    for blob, path, expected in cases:
+        with pytest.raises(JsonRepairError) as exc_info:
+            extractor.extract(blob)
+        assert (exc_info.value.path, exc_info.value.expected) == (path, [expected]), blob
+
+    # 根节点的数字被约束拒绝时跳过整个数字，`-3.0` 里的 `3` 不会被当作答案
+    natural = JsonExtractor({"type": "integer", "minimum": 0})
+    assert natural.extract(b"from -3.0 to 4") == 4
+    assert natural.extract(b"from -1,500 to 2") == 2
+
+    # 不是数字的值报错，而不是变成 0.0
+    with pytest.raises(JsonRepairError) as exc_info:
+        extractor.extract(b'{"score": high}')
+    assert exc_info.value.expected == ["a number"]
+
+    with pytest.raises(ValueError):
+        JsonExtractor({"type": "number", "multipleOf": 0})
+
+
+def test_schema_extractor_array_constraints():
+    schema = {
+        "type": "object",
+        "properties": {
+            "bbox": {
+                "type": "array",
+                "prefixItems": [{"type": "integer"}] * 4,
+                "items": False,
+            },
+            "label": {
+                "type": "array",
+                "prefixItems": [{"type": "string"}, {"type": "number", "maximum": 1}],
+            },
+            "tags": {
+                "type": "array",
+                "items": {"type": "string"},
+                "minItems": 1,
+                "maxItems": 3,
+                "uniqueItems": True,
+            },
+        },
+    }
+    extractor = JsonExtractor(schema)
+    # 元组按位置解析，prefixItems 之外的元素不受约束
+    assert extractor.extract(
+        b'Result: {"bbox": [10, 20, 30, 40], "label": ["cat", 0.9, "extra"], "tags": ["a", "b"]}'
+    ) == {"bbox": [10, 20, 30, 40], "label": ["cat", 0.9, "extra"], "tags": ["a", "b"]}
+
+    cases = [
+        (b'{"bbox": [1, 2, 3, 4, 5]}', "/bbox", "maxItems 4"),
+        (b'{"label": ["cat", 1.5]}', "/label/1", "maximum 1"),
+        (b'{"tags": []}', "/tags", "minItems 1"),
+        (b'{"tags": ["a", "b", "c", "d"]}', "/tags", "maxItems 3"),
+        (b'{"tags": ["a", "b", "a"]}', "/tags", "uniqueItems"),
+    ]
+    for blob, path, expected in cases:
+        with pytest.raises(JsonRepairError) as exc_info:
+            extractor.extract(blob)
+        assert (exc_info.value.path, exc_info.value.expected) == (path, [expected]), blob
+
+    # 旧草案的列表形式 items，其余元素按 additionalItems 解析
+    legacy = JsonExtractor(
+        {
+            "type": "array",
+            "items": [{"type": "string"}, {"type": "integer"}],
+            "additionalItems": {"type": "boolean"},
+        }
+    )
+    assert legacy.extract(b'["x", 1, true, false]') == ["x", 1, True, False]
+
+
+def test_repair_error_carries_position():
+    with pytest.raises(JsonRepairError) as exc_info:
+        repair_json('{"ключ" 1}')
+    assert isinstance(exc_info.value, ValueError)
+    assert exc_info.value.pos == 8  # str 下标，不是字节
+    assert str(exc_info.value).endswith("(char 8)")
+    assert (exc_info.value.lineno, exc_info.value.colno) == (1, 9)
+    assert exc_info.value.expected == ["':'"]
+    assert "ключ" in exc_info.value.snippet
+    with pytest.raises(RecursionError) as exc_info:
+        repair_json('{"ключ": [[1]]}', max_depth=2)
+    assert str(exc_info.value).endswith("(char 10)")
+
+
+def test_schema_extractor_error_points_at_missing_field():
+    extractor = JsonExtractor(
+        {
+            "type": "object",
+            "properties": {"summary": {"type": "string"}, "score": {"type": "number"}},
+            "required": ["summary"],
+        }
+    )
+    with pytest.raises(JsonRepairError) as exc_info:
+        extractor.extract(b"note\n{'score': 10}")
+    assert exc_info.value.expected == ['"summary"']
+    assert exc_info.value.lineno == 2
+    assert exc_info.value.pos == len(b"note\n{'score': 10")
+
+    # 提取的 pos 是字节偏移，消息里的位置也按字节
+    blob = "заметка\n{'score': 10}".encode()
+    with pytest.raises(JsonRepairError) as exc_info:
+        extractor.extract(blob)
+    assert exc_info.value.pos == len(blob) - 1
+    assert str(exc_info.value).endswith(f"(byte {exc_info.value.pos})")